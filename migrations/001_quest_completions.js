@@ -0,0 +1,80 @@
+// Removes duplicated completed tasks so that the unique index on {address, task_id} can be
+// built, then records the quests completed before quest_completions existed. Their experience
+// was already credited when their last task was completed, so they are recorded as credited and
+// adding a task to the quest later does not credit it again.
+//
+// mongosh "<connection_string>/<database>" migrations/001_quest_completions.js
+
+const duplicates = db.completed_tasks.aggregate(
+  [
+    { $sort: { timestamp: 1 } },
+    {
+      $group: {
+        _id: { address: "$address", task_id: "$task_id" },
+        ids: { $push: "$_id" },
+        count: { $sum: 1 },
+      },
+    },
+    { $match: { count: { $gt: 1 } } },
+  ],
+  { allowDiskUse: true }
+);
+let removed = 0;
+duplicates.forEach((duplicate) => {
+  // the earliest completion is kept
+  removed += db.completed_tasks.deleteMany({ _id: { $in: duplicate.ids.slice(1) } })
+    .deletedCount;
+});
+print(`removed ${removed} duplicated completed tasks`);
+
+db.completed_tasks.createIndex({ address: 1, task_id: 1 }, { unique: true });
+db.quest_completions.createIndex({ address: 1, quest_id: 1 }, { unique: true });
+
+db.completed_tasks.aggregate(
+  [
+    {
+      $lookup: {
+        from: "tasks",
+        localField: "task_id",
+        foreignField: "id",
+        as: "task",
+      },
+    },
+    { $unwind: "$task" },
+    {
+      $group: {
+        _id: { address: "$address", quest_id: "$task.quest_id" },
+        done: { $addToSet: "$task_id" },
+        timestamp: { $max: "$timestamp" },
+      },
+    },
+    {
+      $lookup: {
+        from: "tasks",
+        localField: "_id.quest_id",
+        foreignField: "quest_id",
+        as: "tasks",
+      },
+    },
+    { $match: { $expr: { $eq: [{ $size: "$done" }, { $size: "$tasks" }] } } },
+    {
+      $project: {
+        _id: 0,
+        address: "$_id.address",
+        quest_id: "$_id.quest_id",
+        timestamp: 1,
+        credited: { $literal: true },
+      },
+    },
+    {
+      $merge: {
+        into: "quest_completions",
+        on: ["address", "quest_id"],
+        whenMatched: "keepExisting",
+        whenNotMatched: "insert",
+      },
+    },
+  ],
+  { allowDiskUse: true }
+);
+print(`${db.quest_completions.countDocuments()} quest completions recorded`);
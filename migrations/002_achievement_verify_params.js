@@ -0,0 +1,37 @@
+// Writes the verify_params of the achievements whose thresholds used to be hardcoded in their
+// handlers. Only achievements without verify_params are changed and their verify_type is left
+// as is, the frontend picking the endpoint verifying an achievement from it.
+//
+// mongosh "<connection_string>/<database>" migrations/002_achievement_verify_params.js
+
+const tvl = "https://public.starkendefi.xyz/public/aggregates/{addr}";
+const avnu = "https://starknet.api.avnu.fi/v1/takers/{addr}";
+const tiers = [
+  [11, "api_value", 100, tvl, "/total_tvl_dollars"],
+  [12, "api_value", 1000, tvl, "/total_tvl_dollars"],
+  [13, "api_value", 10000, tvl, "/total_tvl_dollars"],
+  [14, "seniority", 90],
+  [15, "seniority", 180],
+  [16, "seniority", 365],
+  [17, "api_value", 500, avnu, "/volumeInUSD"],
+  [18, "api_value", 5000, avnu, "/volumeInUSD"],
+  [19, "api_value", 50000, avnu, "/volumeInUSD"],
+  [23, "quests", 1],
+  [24, "quests", 3],
+  [25, "quests", 10],
+  [26, "quests", 25],
+  [27, "quests", 50],
+];
+
+for (const [id, verifier, threshold, sourceUrl, sourceField] of tiers) {
+  const verifyParams = { verifier: verifier, threshold: Double(threshold) };
+  if (sourceUrl) {
+    verifyParams.source_url = sourceUrl;
+    verifyParams.source_field = sourceField;
+  }
+  const result = db.achievements.updateOne(
+    { id: id, verify_params: { $exists: false } },
+    { $set: { verify_params: verifyParams } }
+  );
+  print(`achievement ${id}: ${result.modifiedCount ? "updated" : "unchanged"}`);
+}
@@ -0,0 +1,28 @@
+// Turns the discord tasks of the partners that had their own callback, with their server
+// hardcoded, into regular discord tasks verified by /quests/discord_fw_callback. Tasks are
+// matched on their quest too and the ones already given a server are left as is.
+//
+// mongosh "<connection_string>/<database>" migrations/003_legacy_discord_tasks.js
+
+const tasks = [
+  // ekubo
+  [9, 39, "1119209474369003600"],
+  // nostra liquidity quest
+  [20, 80, "1002209435868987463"],
+  // focustree engagement
+  [21, 87, "986385497888792598"],
+];
+
+for (const [questId, taskId, guildId] of tasks) {
+  const result = db.tasks.updateOne(
+    { id: taskId, quest_id: questId, discord_guild_id: { $exists: false } },
+    {
+      $set: {
+        task_type: "discord",
+        discord_guild_id: guildId,
+        verify_endpoint: "quests/discord_fw_callback",
+      },
+    }
+  );
+  print(`task ${taskId} of quest ${questId}: ${result.modifiedCount ? "updated" : "unchanged"}`);
+}
@@ -0,0 +1,35 @@
+// Http probe rules used to select the value they compare with a JSONPath like $.data[0].volume.
+// They now take a JSON pointer like /data/0/volume in their field, as the source_field of
+// achievements does. Rules are converted wherever they are nested.
+//
+// mongosh "<connection_string>/<database>" migrations/004_http_probe_fields.js
+
+function toPointer(path) {
+  return path
+    .replace(/^\$/, "")
+    .replace(/\[(\d+)\]/g, ".$1")
+    .split(".")
+    .slice(1)
+    .map((key) => "/" + key.replace(/~/g, "~0").replace(/\//g, "~1"))
+    .join("");
+}
+
+function convert(rule) {
+  if (rule.type === "http_probe" && rule.json_path !== undefined) {
+    rule.field = toPointer(rule.json_path);
+    delete rule.json_path;
+  }
+  (rule.rules || []).forEach(convert);
+  return rule;
+}
+
+let updated = 0;
+db.tasks.find({ task_type: "rule", rule: { $exists: true } }).forEach((task) => {
+  const before = JSON.stringify(task.rule);
+  const rule = convert(task.rule);
+  if (JSON.stringify(rule) !== before) {
+    db.tasks.updateOne({ _id: task._id }, { $set: { rule: rule } });
+    updated += 1;
+  }
+});
+print(`${updated} rule tasks updated`);
@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use mongodb::{
+    bson::{doc, Document},
+    options::UpdateOptions,
+};
+use starknet::{
+    core::types::{BlockId, EmittedEvent, EventFilter, FieldElement},
+    macros::selector,
+    providers::Provider,
+};
+use tokio::time::{sleep, Duration};
+
+use crate::{
+    common::block_sampling::{get_block_hash, remove_orphaned_documents},
+    models::AppState,
+    utils::{to_hex, ACHIEVEMENTS_QUEST_ID},
+};
+
+// blocks scanned per poll so that catching up is done in bounded steps
+const MAX_BLOCK_RANGE: u64 = 1000;
+const EVENTS_CHUNK_SIZE: u64 = 100;
+const CHECKPOINT_ID: &str = "achievement_claims";
+// seconds between polls when claims_update_interval is not set
+const DEFAULT_UPDATE_INTERVAL: u64 = 60;
+
+// on_claim events carry [address, token_id.low, token_id.high, quest_id, task_id] as data,
+// achievements being minted as the tasks of their reserved quest id
+fn parse_claim(event: &EmittedEvent) -> Option<(FieldElement, u32, u64, FieldElement)> {
+    if event.data.len() < 5 {
+        return None;
+    }
+    let quest_id: u64 = event.data[3].try_into().ok()?;
+    if quest_id != ACHIEVEMENTS_QUEST_ID as u64 {
+        return None;
+    }
+    let achievement_id: u64 = event.data[4].try_into().ok()?;
+    Some((
+        event.data[0],
+        achievement_id as u32,
+        event.block_number?,
+        event.block_hash?,
+    ))
+}
+
+async fn index_achievement_claims(state: &AppState, start_block: u64) -> Result<(), String> {
+    let claims_collection = state.db.collection::<Document>("claimed_achievements");
+    let checkpoints_collection = state.db.collection::<Document>("indexer_checkpoints");
+    let nft_contract = FieldElement::from_hex_be(&state.conf.nft_contract.address)
+        .map_err(|e| format!("Invalid nft contract address: {}", e))?;
+    let latest_block = state
+        .provider
+        .block_number()
+        .await
+        .map_err(|e| format!("Error querying block number: {}", e))?;
+
+    // claims recorded by the claim endpoints carry no block and are never removed, the ones read
+    // from events are checked on every poll as a reorganized block may have been read before the
+    // checkpoint hash was
+    let canonical_block =
+        remove_orphaned_documents(state, "claimed_achievements", "block_number").await?;
+
+    let mut from_block = start_block;
+    let checkpoint = checkpoints_collection
+        .find_one(doc! { "_id": CHECKPOINT_ID }, None)
+        .await
+        .map_err(|e| format!("Error querying checkpoint: {}", e))?;
+    if let Some(checkpoint) = checkpoint {
+        let (Ok(block_number), Ok(block_hash)) = (
+            checkpoint.get_i64("block_number"),
+            checkpoint.get_str("block_hash"),
+        ) else {
+            return Err("Invalid achievement claims checkpoint".to_string());
+        };
+        let block_number = block_number as u64;
+        from_block = block_number + 1;
+
+        // a different hash at the checkpoint means the chain was reorganized at some depth below
+        // it, blocks are then indexed again from the last claim that is still canonical
+        let current_hash = get_block_hash(state, BlockId::Number(block_number)).await?;
+        if current_hash.map(to_hex).as_deref() != Some(block_hash) {
+            from_block = canonical_block
+                .map(|block| block + 1)
+                .unwrap_or(start_block)
+                .max(start_block);
+        }
+    }
+    if from_block > latest_block {
+        return Ok(());
+    }
+    let to_block = latest_block.min(from_block + MAX_BLOCK_RANGE - 1);
+
+    let mut continuation_token = None;
+    loop {
+        let filter = EventFilter {
+            from_block: Some(BlockId::Number(from_block)),
+            to_block: Some(BlockId::Number(to_block)),
+            address: Some(nft_contract),
+            keys: Some(vec![vec![selector!("on_claim")]]),
+        };
+        let page = state
+            .provider
+            .get_events(filter, continuation_token, EVENTS_CHUNK_SIZE)
+            .await
+            .map_err(|e| format!("Error querying claim events: {}", e))?;
+
+        for event in page.events {
+            let Some((address, achievement_id, block_number, block_hash)) = parse_claim(&event)
+            else {
+                continue;
+            };
+            // a claim already recorded by a claim endpoint is kept as is
+            let filter = doc! { "address": to_hex(address), "id": achievement_id };
+            let update = doc! { "$setOnInsert": {
+                "address": to_hex(address),
+                "id": achievement_id,
+                "block_number": block_number as i64,
+                "block_hash": to_hex(block_hash),
+            } };
+            let options = UpdateOptions::builder().upsert(true).build();
+            claims_collection
+                .update_one(filter, update, options)
+                .await
+                .map_err(|e| format!("Error saving claim: {}", e))?;
+        }
+
+        continuation_token = page.continuation_token;
+        if continuation_token.is_none() {
+            break;
+        }
+    }
+
+    let Some(block_hash) = get_block_hash(state, BlockId::Number(to_block)).await? else {
+        return Ok(());
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    checkpoints_collection
+        .update_one(
+            doc! { "_id": CHECKPOINT_ID },
+            doc! { "$set": { "block_number": to_block as i64, "block_hash": to_hex(block_hash) } },
+            options,
+        )
+        .await
+        .map_err(|e| format!("Error saving checkpoint: {}", e))?;
+    Ok(())
+}
+
+pub fn run_achievement_claims_indexer(state: Arc<AppState>) {
+    let achievements = &state.conf.achievements;
+    let Some(start_block) = achievements.claims_start_block else {
+        state
+            .logger
+            .info("Achievement claims indexer disabled, no start block configured");
+        return;
+    };
+    let interval = achievements
+        .claims_update_interval
+        .unwrap_or(DEFAULT_UPDATE_INTERVAL);
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = index_achievement_claims(&state, start_block).await {
+                state
+                    .logger
+                    .warning(format!("Error indexing achievement claims: {}", e));
+            }
+            sleep(Duration::from_secs(interval)).await;
+        }
+    });
+}
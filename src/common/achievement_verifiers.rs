@@ -0,0 +1,233 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use starknet::core::types::FieldElement;
+
+use crate::{
+    common::{
+        has_deployed_time::execute_has_deployed_time, verification_rules::validate_json_pointer,
+    },
+    models::{AchievementDocument, AchievementVerifyParams, AppState},
+    utils::{to_hex, AchievementsTrait},
+};
+
+// verify types checked by comparing a value to the threshold of their verify_params
+pub const THRESHOLD_VERIFY_TYPES: [&str; 3] = ["seniority", "api_value", "quests"];
+
+// verify types the frontend can verify, either through a dedicated endpoint of the achievement
+// or its category, or through the threshold verifiers dispatched by /achievements/verify
+pub const SUPPORTED_VERIFY_TYPES: [&str; 5] =
+    ["default", "batched", "seniority", "api_value", "quests"];
+
+pub fn validate_verify_type(verify_type: &str) -> Result<(), String> {
+    if SUPPORTED_VERIFY_TYPES.contains(&verify_type) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unsupported verify_type {}, expected one of {}",
+            verify_type,
+            SUPPORTED_VERIFY_TYPES.join(", ")
+        ))
+    }
+}
+
+// the threshold verifier checking the achievement, if any
+pub fn get_verifier<'a>(verify_type: &'a str, params: &'a AchievementVerifyParams) -> &'a str {
+    params.verifier.as_deref().unwrap_or(verify_type)
+}
+
+pub fn validate_verify_params(
+    verify_type: &str,
+    verify_params: &Option<AchievementVerifyParams>,
+) -> Result<(), String> {
+    let Some(params) = verify_params else {
+        if THRESHOLD_VERIFY_TYPES.contains(&verify_type) {
+            return Err(format!(
+                "verify_params are required for {} achievements",
+                verify_type
+            ));
+        }
+        return Ok(());
+    };
+    let verifier = get_verifier(verify_type, params);
+    if !THRESHOLD_VERIFY_TYPES.contains(&verifier) {
+        if params.verifier.is_some() {
+            return Err(format!(
+                "Unsupported verifier {}, expected one of {}",
+                verifier,
+                THRESHOLD_VERIFY_TYPES.join(", ")
+            ));
+        }
+        return Ok(());
+    }
+    if params.threshold <= 0.0 {
+        return Err("threshold must be positive".to_string());
+    }
+    if verifier == "api_value" {
+        match (&params.source_url, &params.source_field) {
+            (Some(url), Some(field)) if url.contains("{addr}") => validate_json_pointer(field)?,
+            _ => return Err(
+                "api_value achievements require a source_url containing {addr} and a source_field"
+                    .to_string(),
+            ),
+        }
+    }
+    Ok(())
+}
+
+// number of quests having all of their tasks completed by the address
+pub async fn get_completed_quests_count(
+    state: &AppState,
+    addr: &FieldElement,
+) -> Result<u32, String> {
+    let pipeline = vec![
+        doc! {
+            "$match": doc! {
+                "address": addr.to_string()
+            }
+        },
+        doc! {
+            "$lookup": doc! {
+                "from": "tasks",
+                "localField": "task_id",
+                "foreignField": "id",
+                "as": "associatedTask"
+            }
+        },
+        doc! {
+            "$unwind": "$associatedTask"
+        },
+        doc! {
+            "$group": doc! {
+                "_id": "$associatedTask.quest_id",
+                "done": doc! {
+                    "$sum": 1
+                }
+            }
+        },
+        doc! {
+            "$lookup": doc! {
+                "from": "tasks",
+                "localField": "_id",
+                "foreignField": "quest_id",
+                "as": "tasks"
+            }
+        },
+        doc! {
+            "$match": doc! {
+                "$expr": doc! {
+                    "$eq": ["$done", doc! { "$size": "$tasks" }]
+                }
+            }
+        },
+        doc! {
+            "$count": "count"
+        },
+    ];
+    let result = state
+        .db
+        .collection::<Document>("completed_tasks")
+        .aggregate(pipeline, None)
+        .await
+        .map_err(|e| format!("Error querying quests: {}", e))?
+        .try_next()
+        .await
+        .map_err(|e| format!("Error querying quests: {}", e))?;
+    Ok(result
+        .and_then(|result| result.get_i32("count").ok())
+        .unwrap_or(0) as u32)
+}
+
+pub async fn get_api_value(
+    params: &AchievementVerifyParams,
+    addr: &FieldElement,
+) -> Result<f64, String> {
+    let (Some(url), Some(field)) = (&params.source_url, &params.source_field) else {
+        return Err("Achievement has no source".to_string());
+    };
+    let url = url.replace("{addr}", &to_hex(*addr));
+    let json = reqwest::Client::new()
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch achievement source: {}", e))?
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| format!("Failed to get JSON response from achievement source: {}", e))?;
+    json.pointer(field)
+        .and_then(|value| value.as_f64())
+        .ok_or_else(|| "No data found for this address".to_string())
+}
+
+// value compared to the threshold of the achievement, computed by its verifier
+async fn get_verifier_value(
+    state: &Arc<AppState>,
+    achievement: &AchievementDocument,
+    params: &AchievementVerifyParams,
+    addr: &FieldElement,
+) -> Result<f64, String> {
+    match get_verifier(&achievement.verify_type, params) {
+        "seniority" => {
+            let deployed_at = execute_has_deployed_time(state.clone(), addr).await?;
+            let days_passed = (Utc::now().timestamp() - deployed_at as i64) / 86400;
+            Ok(days_passed as f64)
+        }
+        "api_value" => get_api_value(params, addr).await,
+        "quests" => Ok(get_completed_quests_count(state, addr).await? as f64),
+        verifier => Err(format!("No verifier {}", verifier)),
+    }
+}
+
+fn get_unmet_message(verify_type: &str) -> String {
+    match verify_type {
+        "seniority" => "Your wallet is too recent".to_string(),
+        "quests" => "User hasn't completed required number of quests".to_string(),
+        _ => "Your value is too low for this achievement".to_string(),
+    }
+}
+
+// loads an achievement along with its verify_params, checking it uses the expected verifier
+pub async fn get_threshold_achievement(
+    state: &AppState,
+    achievement_id: u32,
+    verifier: Option<&str>,
+) -> Result<(AchievementDocument, AchievementVerifyParams), String> {
+    let achievement = state
+        .get_achievement(achievement_id)
+        .await
+        .map_err(|e| format!("Error querying achievement: {}", e))?
+        .ok_or_else(|| "Invalid achievement id".to_string())?;
+    let params = achievement
+        .verify_params
+        .clone()
+        .ok_or_else(|| "Invalid achievement id".to_string())?;
+    if let Some(verifier) = verifier {
+        if get_verifier(&achievement.verify_type, &params) != verifier {
+            return Err("Invalid achievement id".to_string());
+        }
+    }
+    Ok((achievement, params))
+}
+
+// dispatches the achievement to the verifier of its verify_type and records it once reached
+pub async fn verify_achievement(
+    state: &Arc<AppState>,
+    addr: FieldElement,
+    achievement_id: u32,
+) -> Result<(), String> {
+    let (achievement, params) = get_threshold_achievement(state, achievement_id, None).await?;
+    let value = get_verifier_value(state, &achievement, &params, &addr).await?;
+    if value < params.threshold {
+        return Err(get_unmet_message(get_verifier(
+            &achievement.verify_type,
+            &params,
+        )));
+    }
+    state
+        .upsert_completed_achievement(addr, achievement_id)
+        .await
+        .map_err(|e| format!("{}", e))?;
+    Ok(())
+}
@@ -0,0 +1,273 @@
+use std::future::Future;
+
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement, MaybePendingBlockWithTxHashes},
+    providers::Provider,
+};
+
+use crate::{
+    models::{AppState, BlockSample, BlockSampling, QuestDocument, QuestTaskDocument},
+    utils::to_hex,
+};
+
+// each sample costs one RPC call per contract call of the task
+const MAX_SAMPLES: u32 = 10;
+
+pub fn validate_block_sampling(sampling: &BlockSampling) -> Result<(), String> {
+    if sampling.samples == 0 || sampling.samples > MAX_SAMPLES {
+        return Err(format!("samples must be between 1 and {}", MAX_SAMPLES));
+    }
+    if sampling.window <= 0 {
+        return Err("window must be a positive number of seconds".to_string());
+    }
+    Ok(())
+}
+
+pub async fn get_block_timestamp(state: &AppState, block_id: BlockId) -> Result<u64, String> {
+    match state.provider.get_block_with_tx_hashes(block_id).await {
+        Ok(MaybePendingBlockWithTxHashes::Block(block)) => Ok(block.timestamp),
+        Ok(MaybePendingBlockWithTxHashes::PendingBlock(block)) => Ok(block.timestamp),
+        Err(e) => Err(format!("Error querying block: {}", e)),
+    }
+}
+
+// returns None for the pending block, which has no hash yet
+pub async fn get_block_hash(
+    state: &AppState,
+    block_id: BlockId,
+) -> Result<Option<FieldElement>, String> {
+    match state.provider.get_block_with_tx_hashes(block_id).await {
+        Ok(MaybePendingBlockWithTxHashes::Block(block)) => Ok(Some(block.block_hash)),
+        Ok(MaybePendingBlockWithTxHashes::PendingBlock(_)) => Ok(None),
+        Err(e) => Err(format!("Error querying block: {}", e)),
+    }
+}
+
+// deletes the documents an indexer read from orphaned blocks, walking down from the newest one
+// until it is still in a block with the recorded hash. Block hashes chain, so every document below
+// it is canonical too and the block it returns is where a rescan can start from
+pub async fn remove_orphaned_documents(
+    state: &AppState,
+    collection: &str,
+    block_field: &str,
+) -> Result<Option<u64>, String> {
+    let collection = state.db.collection::<Document>(collection);
+    let pipeline = vec![
+        doc! { "$match": { "block_hash": { "$ne": null } } },
+        doc! { "$group": {
+            "_id": { "block_number": format!("${}", block_field), "block_hash": "$block_hash" },
+        } },
+        doc! { "$sort": { "_id.block_number": -1 } },
+    ];
+    let mut blocks = collection
+        .aggregate(pipeline, None)
+        .await
+        .map_err(|e| format!("Error querying indexed blocks: {}", e))?;
+    while let Some(block) = blocks
+        .try_next()
+        .await
+        .map_err(|e| format!("Error querying indexed blocks: {}", e))?
+    {
+        let block = block
+            .get_document("_id")
+            .map_err(|_| "Invalid indexed block".to_string())?;
+        let (Ok(block_number), Ok(block_hash)) =
+            (block.get_i64("block_number"), block.get_str("block_hash"))
+        else {
+            return Err("Invalid indexed block".to_string());
+        };
+        let current_hash = get_block_hash(state, BlockId::Number(block_number as u64)).await?;
+        if current_hash.map(to_hex).as_deref() == Some(block_hash) {
+            return Ok(Some(block_number as u64));
+        }
+        collection
+            .delete_many(
+                doc! { block_field: block_number, "block_hash": block_hash },
+                None,
+            )
+            .await
+            .map_err(|e| format!("Error removing orphaned documents: {}", e))?;
+    }
+    Ok(None)
+}
+
+// rough starknet block time, only used to guess how far back the search starts
+const ESTIMATED_BLOCK_TIME: u64 = 6;
+
+// first block produced at or after the given timestamp
+pub async fn get_first_block_after(
+    state: &AppState,
+    timestamp: u64,
+    latest_block: u64,
+    latest_timestamp: u64,
+) -> Result<u64, String> {
+    search_first_block(timestamp, latest_block, latest_timestamp, |block_number| {
+        get_block_timestamp(state, BlockId::Number(block_number))
+    })
+    .await
+}
+
+// steps back from the latest block by the estimated number of blocks since the timestamp,
+// doubling the step until a block older than the timestamp is found, then binary searches
+// in between so that a lookup costs a few calls instead of a search from block 0
+async fn search_first_block<F, Fut>(
+    timestamp: u64,
+    latest_block: u64,
+    latest_timestamp: u64,
+    get_timestamp: F,
+) -> Result<u64, String>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = Result<u64, String>>,
+{
+    if latest_timestamp < timestamp {
+        return Ok(latest_block);
+    }
+    let mut high = latest_block;
+    let mut step = ((latest_timestamp - timestamp) / ESTIMATED_BLOCK_TIME).max(1);
+    let mut low = 0;
+    while high > 0 {
+        let candidate = high.saturating_sub(step);
+        if get_timestamp(candidate).await? < timestamp {
+            low = candidate;
+            break;
+        }
+        high = candidate;
+        step = step.saturating_mul(2);
+    }
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if get_timestamp(mid).await? < timestamp {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    Ok(low)
+}
+
+// returns the blocks a task condition must hold at, an empty list meaning the latest block
+pub async fn get_verification_blocks(
+    state: &AppState,
+    task: &QuestTaskDocument,
+) -> Result<Vec<u64>, String> {
+    // a snapshot block configured on the quest takes precedence over sampling
+    let quests_collection = state.db.collection::<QuestDocument>("quests");
+    match quests_collection
+        .find_one(doc! { "id": task.quest_id }, None)
+        .await
+    {
+        Ok(Some(quest)) => {
+            if let Some(snapshot_block) = quest.snapshot_block {
+                return Ok(vec![snapshot_block as u64]);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => return Err(format!("Error querying quest: {}", e)),
+    }
+
+    let Some(sampling) = &task.block_sampling else {
+        return Ok(vec![]);
+    };
+
+    let latest_block = state
+        .provider
+        .block_number()
+        .await
+        .map_err(|e| format!("Error querying block number: {}", e))?;
+    let latest_timestamp = get_block_timestamp(state, BlockId::Number(latest_block)).await?;
+    let first_block = get_first_block_after(
+        state,
+        latest_timestamp.saturating_sub(sampling.window as u64),
+        latest_block,
+        latest_timestamp,
+    )
+    .await?;
+
+    // samples are evenly spread over the window and always include the latest block
+    let samples = sampling.samples as u64;
+    if samples == 1 {
+        return Ok(vec![latest_block]);
+    }
+    let span = latest_block - first_block;
+    let mut blocks: Vec<u64> = (0..samples)
+        .map(|i| first_block + span * i / (samples - 1))
+        .collect();
+    blocks.dedup();
+    Ok(blocks)
+}
+
+// runs the check at every block and stops at the first one where it does not hold
+pub async fn sample_blocks<F, Fut>(
+    blocks: &[u64],
+    check: F,
+) -> Result<(bool, Vec<BlockSample>), String>
+where
+    F: Fn(BlockId) -> Fut,
+    Fut: Future<Output = Result<(bool, Vec<FieldElement>), String>>,
+{
+    if blocks.is_empty() {
+        let (passed, _) = check(BlockId::Tag(BlockTag::Latest)).await?;
+        return Ok((passed, vec![]));
+    }
+
+    let mut samples = vec![];
+    for block_number in blocks {
+        let (passed, result) = check(BlockId::Number(*block_number)).await?;
+        samples.push(BlockSample {
+            block_number: *block_number,
+            result: result.into_iter().map(to_hex).collect(),
+            passed,
+        });
+        if !passed {
+            return Ok((false, samples));
+        }
+    }
+    Ok((true, samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // a chain of 1M blocks with a block every 10 seconds
+    fn block_timestamp(block_number: u64) -> u64 {
+        1_000_000 + block_number * 10
+    }
+
+    async fn search(timestamp: u64, calls: &AtomicUsize) -> u64 {
+        let latest_block = 999_999;
+        search_first_block(
+            timestamp,
+            latest_block,
+            block_timestamp(latest_block),
+            |block_number| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async move { Ok(block_timestamp(block_number)) }
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_search_first_block() {
+        let calls = AtomicUsize::new(0);
+        // one hour window
+        assert_eq!(
+            search(block_timestamp(999_999) - 3600, &calls).await,
+            999_639
+        );
+        assert!(calls.load(Ordering::SeqCst) <= 12);
+
+        let calls = AtomicUsize::new(0);
+        // a timestamp between two blocks resolves to the next one
+        assert_eq!(search(block_timestamp(500_000) - 5, &calls).await, 500_000);
+        assert_eq!(search(0, &calls).await, 0);
+        assert_eq!(search(block_timestamp(999_999) + 1, &calls).await, 999_999);
+    }
+}
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use mongodb::{
+    bson::{doc, to_bson, Document},
+    options::UpdateOptions,
+};
+use num_bigint::BigUint;
+use starknet::{
+    core::types::{BlockId, EmittedEvent, EventFilter, FieldElement},
+    macros::selector,
+    providers::Provider,
+};
+use tokio::time::{sleep, Duration};
+
+use crate::{
+    common::block_sampling::{get_block_hash, remove_orphaned_documents},
+    models::{AppState, BoostClaimCursor, BoostClaimDocument},
+    utils::to_hex,
+};
+
+// blocks scanned per poll so that catching up is done in bounded steps
+const MAX_BLOCK_RANGE: u64 = 1000;
+const EVENTS_CHUNK_SIZE: u64 = 100;
+const CHECKPOINT_ID: &str = "boost_claims";
+// seconds between polls when claims_update_interval is not set
+const DEFAULT_UPDATE_INTERVAL: u64 = 30;
+
+// OnClaim events carry [timestamp, amount.low, amount.high, address, boost_id] as data, followed
+// by the token paid on contracts accepting a claim per token
+fn parse_claim(event: &EmittedEvent) -> Option<BoostClaimDocument> {
+    if event.data.len() < 5 {
+        return None;
+    }
+    let block_number = event.block_number?;
+    let block_hash = event.block_hash?;
+    let timestamp: u64 = event.data[0].try_into().ok()?;
+    let amount = (BigUint::from_bytes_be(&event.data[2].to_bytes_be()) << 128)
+        + BigUint::from_bytes_be(&event.data[1].to_bytes_be());
+    let boost_id: u64 = event.data[4].try_into().ok()?;
+    Some(BoostClaimDocument {
+        id: boost_id as i32,
+        winner: to_hex(event.data[3]),
+        amount: amount.to_string(),
+        timestamp: timestamp as i64,
+        transaction_hash: to_hex(event.transaction_hash),
+        token: event.data.get(5).map(|token| to_hex(*token)),
+        block_hash: Some(to_hex(block_hash)),
+        _cursor: BoostClaimCursor {
+            from: block_number,
+            to: None,
+        },
+    })
+}
+
+async fn index_boost_claims(
+    state: &AppState,
+    contract: FieldElement,
+    start_block: u64,
+) -> Result<(), String> {
+    let claims_collection = state.db.collection::<BoostClaimDocument>("boost_claims");
+    let checkpoints_collection = state.db.collection::<Document>("indexer_checkpoints");
+    let latest_block = state
+        .provider
+        .block_number()
+        .await
+        .map_err(|e| format!("Error querying block number: {}", e))?;
+
+    // checked on every poll as events of a reorganized block may have been read before the
+    // checkpoint hash was
+    let canonical_block = remove_orphaned_documents(state, "boost_claims", "_cursor.from").await?;
+
+    let mut from_block = start_block;
+    let checkpoint = checkpoints_collection
+        .find_one(doc! { "_id": CHECKPOINT_ID }, None)
+        .await
+        .map_err(|e| format!("Error querying checkpoint: {}", e))?;
+    if let Some(checkpoint) = checkpoint {
+        let (Ok(block_number), Ok(block_hash)) = (
+            checkpoint.get_i64("block_number"),
+            checkpoint.get_str("block_hash"),
+        ) else {
+            return Err("Invalid boost claims checkpoint".to_string());
+        };
+        let block_number = block_number as u64;
+        from_block = block_number + 1;
+
+        // a different hash at the checkpoint means the chain was reorganized at some depth below
+        // it, blocks are then indexed again from the last claim that is still canonical
+        let current_hash = get_block_hash(state, BlockId::Number(block_number)).await?;
+        if current_hash.map(to_hex).as_deref() != Some(block_hash) {
+            from_block = canonical_block
+                .map(|block| block + 1)
+                .unwrap_or(start_block)
+                .max(start_block);
+        }
+    }
+    if from_block > latest_block {
+        return Ok(());
+    }
+    let to_block = latest_block.min(from_block + MAX_BLOCK_RANGE - 1);
+
+    let mut continuation_token = None;
+    loop {
+        let filter = EventFilter {
+            from_block: Some(BlockId::Number(from_block)),
+            to_block: Some(BlockId::Number(to_block)),
+            address: Some(contract),
+            keys: Some(vec![vec![selector!("OnClaim")]]),
+        };
+        let page = state
+            .provider
+            .get_events(filter, continuation_token, EVENTS_CHUNK_SIZE)
+            .await
+            .map_err(|e| format!("Error querying claim events: {}", e))?;
+
+        for event in page.events {
+            let Some(claim) = parse_claim(&event) else {
+                continue;
+            };
+            let filter = doc! {
+                "id": claim.id,
+                "winner": &claim.winner,
+                "transaction_hash": &claim.transaction_hash,
+                "token": &claim.token,
+                "_cursor.to": null,
+            };
+            let claim = to_bson(&claim).map_err(|e| format!("Error serializing claim: {}", e))?;
+            let options = UpdateOptions::builder().upsert(true).build();
+            claims_collection
+                .update_one(filter, doc! { "$setOnInsert": claim }, options)
+                .await
+                .map_err(|e| format!("Error saving claim: {}", e))?;
+        }
+
+        continuation_token = page.continuation_token;
+        if continuation_token.is_none() {
+            break;
+        }
+    }
+
+    let Some(block_hash) = get_block_hash(state, BlockId::Number(to_block)).await? else {
+        return Ok(());
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    checkpoints_collection
+        .update_one(
+            doc! { "_id": CHECKPOINT_ID },
+            doc! { "$set": { "block_number": to_block as i64, "block_hash": to_hex(block_hash) } },
+            options,
+        )
+        .await
+        .map_err(|e| format!("Error saving checkpoint: {}", e))?;
+    Ok(())
+}
+
+pub fn run_boost_claims_indexer(state: Arc<AppState>) {
+    let boost = &state.conf.quest_boost;
+    let (Some(contract), Some(start_block)) = (boost.contract, boost.claims_start_block) else {
+        state
+            .logger
+            .info("Boost claims indexer disabled, no contract or start block configured");
+        return;
+    };
+    let interval = boost
+        .claims_update_interval
+        .unwrap_or(DEFAULT_UPDATE_INTERVAL);
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = index_boost_claims(&state, contract, start_block).await {
+                state
+                    .logger
+                    .warning(format!("Error indexing boost claims: {}", e));
+            }
+            sleep(Duration::from_secs(interval)).await;
+        }
+    });
+}
@@ -0,0 +1,189 @@
+use num_bigint::BigUint;
+use starknet::core::types::FieldElement;
+
+use crate::{
+    models::{
+        AppState, BoostClaimDocument, BoostDistribution, BoostReward, BoostRewardQuery, BoostTable,
+        BoostTier,
+    },
+    utils::{parse_decimal_amount, to_hex},
+};
+
+// resolves the extra rewards of a boost against the token registry
+pub fn resolve_boost_rewards(
+    state: &AppState,
+    rewards: &Option<Vec<BoostRewardQuery>>,
+) -> Result<Option<Vec<BoostReward>>, String> {
+    let Some(rewards) = rewards else {
+        return Ok(None);
+    };
+    if !rewards.is_empty() && !has_multi_token_claims(state) {
+        return Err("The boost contract only pays the main token of a boost".to_string());
+    }
+    rewards
+        .iter()
+        .map(|reward| {
+            let token = state
+                .conf
+                .tokens
+                .find(&reward.token)
+                .ok_or_else(|| format!("Unknown token {}", reward.token))?;
+            parse_decimal_amount(&reward.amount, token.decimals as u32)?;
+            Ok(BoostReward {
+                token: to_hex(token.contract),
+                amount: reward.amount.clone(),
+                decimals: token.decimals,
+            })
+        })
+        .collect::<Result<Vec<BoostReward>, String>>()
+        .map(Some)
+}
+
+pub fn validate_boost_distribution(
+    distribution: &Option<BoostDistribution>,
+    tiers: &Option<Vec<BoostTier>>,
+    amount: &str,
+    decimals: i64,
+) -> Result<(), String> {
+    let amount = parse_decimal_amount(amount, decimals as u32)?;
+
+    // tiers are ignored by the other distributions
+    let Some(BoostDistribution::Tiered) = distribution else {
+        return Ok(());
+    };
+    let tiers = match tiers {
+        Some(tiers) if !tiers.is_empty() => tiers,
+        _ => return Err("tiered distribution requires at least one tier".to_string()),
+    };
+
+    // tiers cover consecutive rank ranges, the first one starting at rank 1
+    let mut previous_rank = 0;
+    let mut total = BigUint::from(0u32);
+    for tier in tiers {
+        if tier.up_to_rank <= previous_rank {
+            return Err("tiers must be sorted by strictly increasing up_to_rank".to_string());
+        }
+        let tier_amount = parse_decimal_amount(&tier.amount, decimals as u32)?;
+        if tier_amount == BigUint::from(0u32) {
+            return Err("tier amounts must be positive".to_string());
+        }
+        total += tier_amount * (tier.up_to_rank - previous_rank);
+        previous_rank = tier.up_to_rank;
+    }
+    if total > amount {
+        return Err(format!(
+            "tiers distribute {} while the boost amount is {}",
+            total, amount
+        ));
+    }
+    Ok(())
+}
+
+// number of completers a boost pays out, None meaning all of them
+pub fn get_max_winners(boost: &BoostTable) -> Option<usize> {
+    match boost.distribution {
+        None | Some(BoostDistribution::Raffle) | Some(BoostDistribution::Fcfs) => {
+            Some(boost.num_of_winners as usize)
+        }
+        Some(BoostDistribution::ProRata) => None,
+        Some(BoostDistribution::Tiered) => boost
+            .tiers
+            .as_ref()
+            .and_then(|tiers| tiers.last())
+            .map(|tier| tier.up_to_rank as usize),
+    }
+}
+
+pub fn has_multi_token_claims(state: &AppState) -> bool {
+    state.conf.quest_boost.multi_token_claims.unwrap_or(false)
+}
+
+// every token paid by a boost, starting with its main token
+pub fn get_boost_rewards(boost: &BoostTable) -> Vec<BoostReward> {
+    let mut rewards = vec![BoostReward {
+        token: boost.token.clone(),
+        amount: boost.amount.clone(),
+        decimals: boost.token_decimals,
+    }];
+    rewards.extend(boost.rewards.clone().unwrap_or_default());
+    rewards
+}
+
+// amounts in the tokens smallest unit owed to the winner at the given index of the winner list
+pub fn get_reward_amounts(
+    boost: &BoostTable,
+    rank: usize,
+) -> Result<Vec<(BoostReward, BigUint)>, String> {
+    let main_total = parse_decimal_amount(&boost.amount, boost.token_decimals as u32)?;
+    if main_total == BigUint::from(0u32) {
+        return Err("Boost amount is zero".to_string());
+    }
+
+    // each token pays numerator / denominator of its own total
+    let (numerator, denominator) = match boost.distribution {
+        None | Some(BoostDistribution::Raffle) | Some(BoostDistribution::Fcfs) => {
+            if boost.num_of_winners <= 0 {
+                return Err("Boost has no winners".to_string());
+            }
+            (
+                BigUint::from(1u32),
+                BigUint::from(boost.num_of_winners as u64),
+            )
+        }
+        Some(BoostDistribution::ProRata) => {
+            let num_of_winners = boost.winner.as_ref().map(|w| w.len()).unwrap_or(0);
+            if num_of_winners == 0 {
+                return Err("Boost has no winners".to_string());
+            }
+            (BigUint::from(1u32), BigUint::from(num_of_winners))
+        }
+        Some(BoostDistribution::Tiered) => {
+            let tier = boost
+                .tiers
+                .as_ref()
+                .and_then(|tiers| tiers.iter().find(|tier| rank < tier.up_to_rank as usize))
+                .ok_or_else(|| format!("No tier found for rank {}", rank + 1))?;
+            let tier_amount = parse_decimal_amount(&tier.amount, boost.token_decimals as u32)?;
+            (tier_amount, main_total)
+        }
+    };
+
+    get_boost_rewards(boost)
+        .into_iter()
+        .map(|reward| {
+            let total = parse_decimal_amount(&reward.amount, reward.decimals as u32)?;
+            let amount = total * &numerator / &denominator;
+            Ok((reward, amount))
+        })
+        .collect()
+}
+
+// amounts a winner can claim, extra reward tokens being left out while the contract only
+// accepts one claim per boost and address
+pub fn get_claimable_amounts(
+    state: &AppState,
+    boost: &BoostTable,
+    rank: usize,
+) -> Result<Vec<(BoostReward, BigUint)>, String> {
+    let mut amounts = get_reward_amounts(boost, rank)?;
+    if !has_multi_token_claims(state) {
+        amounts.truncate(1);
+    }
+    Ok(amounts)
+}
+
+// tokens are compared as addresses as they may not be padded the same way
+pub fn is_same_token(token: &str, other: &str) -> bool {
+    match (
+        FieldElement::from_hex_be(token),
+        FieldElement::from_hex_be(other),
+    ) {
+        (Ok(token), Ok(other)) => token == other,
+        _ => token == other,
+    }
+}
+
+// claims emitted before claims carried their token were paid in the main token of the boost
+pub fn is_claim_of_token(claim: &BoostClaimDocument, boost: &BoostTable, token: &str) -> bool {
+    is_same_token(claim.token.as_deref().unwrap_or(&boost.token), token)
+}
@@ -0,0 +1,173 @@
+use mongodb::bson::doc;
+use reqwest::{header::AUTHORIZATION, StatusCode};
+use serde::Deserialize;
+use starknet::core::types::FieldElement;
+
+use crate::{
+    common::linked_accounts::{get_access_token, record_account_task},
+    models::{AppState, QuestTaskDocument},
+    utils::CompletedTasksTrait,
+};
+
+const API_URL: &str = "https://discord.com/api";
+
+#[derive(Deserialize, Debug)]
+struct Guild {
+    id: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct Member {
+    roles: Vec<String>,
+}
+
+// guilds the user of the token is a member of
+async fn get_guilds(access_token: &str) -> Result<Vec<Guild>, String> {
+    reqwest::Client::new()
+        .get(format!("{}/users/@me/guilds", API_URL))
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to get user info: {}", e))?
+        .json()
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to get JSON response while fetching user info: {}",
+                e
+            )
+        })
+}
+
+// roles of the user in the guild, None if not a member. The bot configured reads them when set,
+// the guilds.members.read scope of the user token being needed otherwise
+pub async fn get_member_roles(
+    state: &AppState,
+    user_id: &str,
+    access_token: &str,
+    guild_id: &str,
+) -> Result<Option<Vec<String>>, String> {
+    let client = reqwest::Client::new();
+    let bot_token = state.conf.discord.bot_token.as_ref();
+    let request = match bot_token {
+        Some(bot_token) => client
+            .get(format!(
+                "{}/guilds/{}/members/{}",
+                API_URL, guild_id, user_id
+            ))
+            .header(AUTHORIZATION, format!("Bot {}", bot_token)),
+        None => client
+            .get(format!("{}/users/@me/guilds/{}/member", API_URL, guild_id))
+            .bearer_auth(access_token),
+    };
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to get member info: {}", e))?;
+    match response.status() {
+        StatusCode::NOT_FOUND => return Ok(None),
+        // the bot is misconfigured or not in the server, linking again would not help the user
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN if bot_token.is_some() => {
+            state.logger.warning(format!(
+                "Discord bot can't read the members of server {}: {}",
+                guild_id,
+                response.status()
+            ));
+            return Err("Discord roles of this server can't be checked for now".to_string());
+        }
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            return Err(
+                "Please link your Discord account again to share your server roles".to_string(),
+            )
+        }
+        _ => {}
+    }
+    let member: Member = response.json().await.map_err(|e| {
+        format!(
+            "Failed to get JSON response while fetching member info: {}",
+            e
+        )
+    })?;
+    Ok(Some(member.roles))
+}
+
+// checks the user holds any of the roles the task requires, if any
+async fn verify_roles(
+    state: &AppState,
+    user_id: &str,
+    access_token: &str,
+    task: &QuestTaskDocument,
+) -> Result<(), String> {
+    let (Some(guild_id), Some(role_ids)) = (&task.discord_guild_id, &task.discord_role_ids) else {
+        return Ok(());
+    };
+    if role_ids.is_empty() {
+        return Ok(());
+    }
+    match get_member_roles(state, user_id, access_token, guild_id).await? {
+        None => Err("You're not part of the Discord server".to_string()),
+        Some(roles) if roles.iter().any(|role| role_ids.contains(role)) => Ok(()),
+        Some(_) => Err("You don't have the required role in the Discord server".to_string()),
+    }
+}
+
+pub fn get_callback_uri(state: &AppState) -> String {
+    format!(
+        "{}/quests/discord_fw_callback",
+        state.conf.variables.api_link
+    )
+}
+
+pub async fn get_task(
+    state: &AppState,
+    quest_id: i64,
+    task_id: u32,
+) -> Result<QuestTaskDocument, String> {
+    state
+        .db
+        .collection::<QuestTaskDocument>("tasks")
+        .find_one(
+            doc! { "quest_id": quest_id, "id": task_id, "task_type": "discord" },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error querying task: {}", e))?
+        .ok_or_else(|| "Error querying task".to_string())
+}
+
+// checks the linked user is a member of the server of the task with the roles it requires
+pub async fn verify_membership(
+    state: &AppState,
+    user_id: &str,
+    access_token: &str,
+    task: &QuestTaskDocument,
+) -> Result<(), String> {
+    let guild_id = task
+        .discord_guild_id
+        .as_ref()
+        .ok_or_else(|| "Task has no Discord server".to_string())?;
+    let guilds = get_guilds(access_token).await?;
+    if !guilds.iter().any(|guild| &guild.id == guild_id) {
+        return Err("You're not part of the Discord server".to_string());
+    }
+    verify_roles(state, user_id, access_token, task).await
+}
+
+// verifies a discord task of the quest with the account linked to the address and records it
+// as completed
+pub async fn verify_task(
+    state: &AppState,
+    addr: FieldElement,
+    quest_id: i64,
+    task_id: u32,
+) -> Result<(), String> {
+    let task = get_task(state, quest_id, task_id).await?;
+    let (account, access_token) = get_access_token(state, addr, "discord").await?;
+    verify_membership(state, &account.account_id, &access_token, &task).await?;
+    record_account_task(state, &account, task_id).await?;
+    state
+        .upsert_completed_task(addr, task_id)
+        .await
+        .map_err(|e| format!("{}", e))?;
+    Ok(())
+}
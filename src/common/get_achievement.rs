@@ -54,6 +54,7 @@ pub async fn get_achievement(
               "id": "$achievement.id",
               "completed": { "$ne": [{ "$size": "$achieved" }, 0] },
               "verify_type": "$achievement.verify_type",
+              "verify_params": "$achievement.verify_params",
             }
           }
         },
@@ -0,0 +1,158 @@
+use chrono::Utc;
+use mongodb::{
+    bson::{doc, Document},
+    Collection, Database,
+};
+
+use crate::models::SeasonDocument;
+
+pub const DAY_MS: i64 = 86_400_000;
+
+// start of the UTC day a millisecond timestamp belongs to
+pub fn get_day_bucket(timestamp: i64) -> i64 {
+    timestamp - timestamp.rem_euclid(DAY_MS)
+}
+
+// stages summing the daily buckets of [start, end) into {_id, experience, timestamp} rows, both
+// bounds being rounded out to whole days so that the days they fall in are included
+pub fn get_window_stages(start: i64, end: Option<i64>) -> Vec<Document> {
+    let mut day_filter = doc! { "$gte": get_day_bucket(start) };
+    if let Some(end) = end {
+        day_filter.insert("$lt", get_day_bucket(end - 1) + DAY_MS);
+    }
+    vec![
+        doc! {
+            "$match": doc! {
+                "day": day_filter
+            }
+        },
+        doc! {
+            "$group": doc! {
+                "_id": "$address",
+                "experience": doc! {
+                    "$sum": "$experience"
+                },
+                "timestamp": doc! {
+                    "$max": "$timestamp"
+                }
+            }
+        },
+    ]
+}
+
+// returns the collection to rank along with the stages producing its rows, windows being
+// rounded to whole UTC days: week and month cover the last 7 and 30 days including today
+pub fn get_leaderboard_source(
+    db: &Database,
+    duration: &str,
+    start: Option<i64>,
+    end: Option<i64>,
+) -> Result<(Collection<Document>, Vec<Document>), String> {
+    let today = get_day_bucket(Utc::now().timestamp_millis());
+    let (start, end) = match duration {
+        "all" => return Ok((db.collection("leaderboard_table"), vec![])),
+        "week" => (today - 6 * DAY_MS, None),
+        "month" => (today - 29 * DAY_MS, None),
+        "custom" => match start {
+            Some(start) => (start, end),
+            None => return Err("A custom duration requires a start".to_string()),
+        },
+        _ => return Err("Invalid duration".to_string()),
+    };
+    Ok((
+        db.collection("leaderboard_daily"),
+        get_window_stages(start, end),
+    ))
+}
+
+// seasons are ranked like custom windows bounded by their start and end
+pub async fn get_season_source(
+    db: &Database,
+    season_id: i32,
+) -> Result<(Collection<Document>, Vec<Document>), String> {
+    let season = db
+        .collection::<SeasonDocument>("seasons")
+        .find_one(doc! { "id": season_id }, None)
+        .await
+        .map_err(|e| format!("Error querying season: {}", e))?
+        .ok_or_else(|| format!("Season {} not found", season_id))?;
+    Ok((
+        db.collection("leaderboard_daily"),
+        get_window_stages(season.start, season.end),
+    ))
+}
+
+// stages turning completed tasks into {_id, experience, timestamp} rows where a quest counts
+// once all of its tasks are done, at the time its last task was completed
+fn get_completion_stages(task_filter: Document, quest_filter: Document) -> Vec<Document> {
+    vec![
+        doc! {
+            "$lookup": doc! {
+                "from": "tasks",
+                "localField": "task_id",
+                "foreignField": "id",
+                "as": "task"
+            }
+        },
+        doc! { "$unwind": "$task" },
+        doc! { "$match": task_filter },
+        doc! {
+            "$lookup": doc! {
+                "from": "quests",
+                "localField": "task.quest_id",
+                "foreignField": "id",
+                "as": "quest"
+            }
+        },
+        doc! { "$unwind": "$quest" },
+        doc! { "$match": quest_filter },
+        doc! {
+            "$group": doc! {
+                "_id": doc! {
+                    "address": "$address",
+                    "quest_id": "$task.quest_id"
+                },
+                "done": doc! { "$sum": 1 },
+                "experience": doc! { "$first": "$quest.experience" },
+                "timestamp": doc! { "$max": "$timestamp" }
+            }
+        },
+        doc! {
+            "$lookup": doc! {
+                "from": "tasks",
+                "localField": "_id.quest_id",
+                "foreignField": "quest_id",
+                "as": "tasks"
+            }
+        },
+        doc! {
+            "$match": doc! {
+                "$expr": doc! {
+                    "$eq": ["$done", doc! { "$size": "$tasks" }]
+                }
+            }
+        },
+        doc! {
+            "$group": doc! {
+                "_id": "$_id.address",
+                "experience": doc! { "$sum": "$experience" },
+                "timestamp": doc! { "$max": "$timestamp" }
+            }
+        },
+    ]
+}
+
+// every completer of a quest earns the same experience so ranks follow completion time
+pub fn get_quest_source(db: &Database, quest_id: u32) -> (Collection<Document>, Vec<Document>) {
+    (
+        db.collection("completed_tasks"),
+        get_completion_stages(doc! { "task.quest_id": quest_id }, doc! {}),
+    )
+}
+
+pub fn get_category_source(db: &Database, category: &str) -> (Collection<Document>, Vec<Document>) {
+    (
+        db.collection("completed_tasks"),
+        get_completion_stages(doc! {}, doc! { "quest.category": category }),
+    )
+}
@@ -0,0 +1,507 @@
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, to_bson, DateTime, Document},
+    options::{IndexOptions, UpdateOptions},
+    Database, IndexModel,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use reqwest::{header::USER_AGENT, Url};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use starknet::core::types::FieldElement;
+
+use crate::{
+    common::xp_ledger::is_duplicate_key,
+    models::{AppState, LinkedAccountDocument},
+};
+
+pub const PROVIDERS: [&str; 4] = ["discord", "twitter", "github", "telegram"];
+// a pending authorization must be completed within 10 minutes
+const OAUTH_STATE_TTL_MS: i64 = 600_000;
+// access tokens are refreshed a minute before they expire
+const TOKEN_EXPIRY_MARGIN_MS: i64 = 60_000;
+
+// a social account can only be linked to one address and an address to one account per provider
+pub async fn add_linked_accounts_indexes(db: &Database) {
+    let accounts = db.collection::<Document>("linked_accounts");
+    for keys in [
+        doc! { "provider": 1, "account_id": 1 },
+        doc! { "address": 1, "provider": 1 },
+    ] {
+        let unique_options = IndexOptions::builder().unique(true).build();
+        let index = IndexModel::builder()
+            .keys(keys)
+            .options(unique_options)
+            .build();
+        accounts.create_index(index, None).await.unwrap();
+    }
+    let ttl_options = IndexOptions::builder()
+        .expire_after(Duration::from_secs(0))
+        .build();
+    let ttl_index = IndexModel::builder()
+        .keys(doc! { "expires_at": 1 })
+        .options(ttl_options)
+        .build();
+    db.collection::<Document>("oauth_states")
+        .create_index(ttl_index, None)
+        .await
+        .unwrap();
+
+    let unique_options = IndexOptions::builder().unique(true).build();
+    let account_task_index = IndexModel::builder()
+        .keys(doc! { "provider": 1, "account_id": 1, "task_id": 1 })
+        .options(unique_options)
+        .build();
+    db.collection::<Document>("account_completed_tasks")
+        .create_index(account_task_index, None)
+        .await
+        .unwrap();
+}
+
+// endpoints and credentials of a provider linked through OAuth2
+pub struct OAuthProvider {
+    pub name: &'static str,
+    authorize_url: &'static str,
+    token_url: &'static str,
+    identity_url: &'static str,
+    scopes: &'static str,
+    client_id: String,
+    client_secret: String,
+    // X requires PKCE and its client credentials as basic auth
+    pkce: bool,
+}
+
+pub fn get_oauth_provider(state: &AppState, provider: &str) -> Option<OAuthProvider> {
+    match provider {
+        "discord" => Some(OAuthProvider {
+            name: "discord",
+            authorize_url: "https://discord.com/oauth2/authorize",
+            token_url: "https://discord.com/api/oauth2/token",
+            identity_url: "https://discord.com/api/users/@me",
+            scopes: "identify guilds guilds.members.read",
+            client_id: state.conf.discord.oauth2_clientid.clone(),
+            client_secret: state.conf.discord.oauth2_secret.clone(),
+            pkce: false,
+        }),
+        "twitter" => Some(OAuthProvider {
+            name: "twitter",
+            authorize_url: "https://twitter.com/i/oauth2/authorize",
+            token_url: "https://api.twitter.com/2/oauth2/token",
+            identity_url: "https://api.twitter.com/2/users/me",
+            scopes: "tweet.read users.read follows.read like.read offline.access",
+            client_id: state.conf.twitter.oauth2_clientid.clone(),
+            client_secret: state.conf.twitter.oauth2_secret.clone(),
+            pkce: true,
+        }),
+        "github" => state.conf.github.as_ref().map(|github| OAuthProvider {
+            name: "github",
+            authorize_url: "https://github.com/login/oauth/authorize",
+            token_url: "https://github.com/login/oauth/access_token",
+            identity_url: "https://api.github.com/user",
+            scopes: "read:user",
+            client_id: github.oauth2_clientid.clone(),
+            client_secret: github.oauth2_secret.clone(),
+            pkce: false,
+        }),
+        _ => None,
+    }
+}
+
+pub fn get_redirect_uri(state: &AppState) -> String {
+    format!("{}/linked_accounts/callback", state.conf.variables.api_link)
+}
+
+fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+// starts an authorization linking an account of the provider to the address, returning the url
+// the user must be sent to. The PKCE verifier stays on the server, keyed by the state the
+// provider sends back to the callback
+pub async fn create_authorization(
+    state: &AppState,
+    provider: &OAuthProvider,
+    addr: FieldElement,
+    quest_id: Option<i64>,
+    task_id: Option<u32>,
+) -> Result<String, String> {
+    let oauth_state = random_string(32);
+    let code_verifier = random_string(64);
+    let expires_at = DateTime::from_millis(Utc::now().timestamp_millis() + OAUTH_STATE_TTL_MS);
+    state
+        .db
+        .collection::<Document>("oauth_states")
+        .insert_one(
+            doc! {
+                "_id": &oauth_state,
+                "provider": provider.name,
+                "address": addr.to_string(),
+                "code_verifier": &code_verifier,
+                "quest_id": quest_id,
+                "task_id": task_id,
+                "expires_at": expires_at,
+            },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error saving authorization: {}", e))?;
+
+    let code_challenge = provider
+        .pkce
+        .then(|| URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes())));
+    get_authorize_url(
+        provider,
+        &get_redirect_uri(state),
+        &oauth_state,
+        code_challenge,
+    )
+}
+
+pub fn get_authorize_url(
+    provider: &OAuthProvider,
+    redirect_uri: &str,
+    oauth_state: &str,
+    code_challenge: Option<String>,
+) -> Result<String, String> {
+    let mut params = vec![
+        ("response_type", "code".to_string()),
+        ("client_id", provider.client_id.clone()),
+        ("redirect_uri", redirect_uri.to_string()),
+        ("scope", provider.scopes.to_string()),
+        ("state", oauth_state.to_string()),
+    ];
+    if let Some(code_challenge) = code_challenge {
+        params.push(("code_challenge", code_challenge));
+        params.push(("code_challenge_method", "S256".to_string()));
+    }
+    let url = Url::parse_with_params(provider.authorize_url, &params)
+        .map_err(|e| format!("Error building authorization url: {}", e))?;
+    Ok(url.to_string())
+}
+
+// the pending authorization a callback completes, consumed so that a state is only used once
+pub async fn take_authorization(
+    state: &AppState,
+    oauth_state: &str,
+) -> Result<Option<Document>, String> {
+    let authorization = state
+        .db
+        .collection::<Document>("oauth_states")
+        .find_one_and_delete(doc! { "_id": oauth_state }, None)
+        .await
+        .map_err(|e| format!("Error querying authorization: {}", e))?;
+    // expired states may not have been dropped by mongo yet
+    let now = DateTime::from_millis(Utc::now().timestamp_millis());
+    Ok(authorization.filter(|authorization| {
+        authorization
+            .get_datetime("expires_at")
+            .map(|expires_at| *expires_at > now)
+            .unwrap_or(false)
+    }))
+}
+
+async fn request_token(provider: &OAuthProvider, params: &[(&str, &str)]) -> Result<Value, String> {
+    let mut params = params.to_vec();
+    params.push(("client_id", provider.client_id.as_str()));
+    let request = reqwest::Client::new()
+        .post(provider.token_url)
+        .header("Accept", "application/json");
+    let request = if provider.pkce {
+        request.basic_auth(&provider.client_id, Some(&provider.client_secret))
+    } else {
+        params.push(("client_secret", provider.client_secret.as_str()));
+        request
+    };
+    let json: Value = request
+        .form(&params)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to get access token: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to get JSON response while fetching token: {}", e))?;
+    if json["access_token"].as_str().is_none() {
+        return Err(format!(
+            "Failed to get 'access_token' from JSON response : {:?}",
+            json
+        ));
+    }
+    Ok(json)
+}
+
+// id and username of the account the token belongs to
+async fn get_identity(
+    provider: &OAuthProvider,
+    access_token: &str,
+) -> Result<(String, String), String> {
+    let json: Value = reqwest::Client::new()
+        .get(provider.identity_url)
+        .bearer_auth(access_token)
+        .header(USER_AGENT, "starknet-quest")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to get user info: {}", e))?
+        .json()
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to get JSON response while fetching user info: {}",
+                e
+            )
+        })?;
+    let (id, username) = match provider.name {
+        "twitter" => (&json["data"]["id"], &json["data"]["username"]),
+        "github" => (&json["id"], &json["login"]),
+        _ => (&json["id"], &json["username"]),
+    };
+    // github ids are numbers
+    let id = match id {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    };
+    match (id, username.as_str()) {
+        (Some(id), Some(username)) => Ok((id, username.to_string())),
+        _ => Err("Failed to get user info".to_string()),
+    }
+}
+
+fn get_expiry(token: &Value, now: i64) -> Option<i64> {
+    token["expires_in"]
+        .as_i64()
+        .map(|expires_in| now + expires_in * 1000)
+}
+
+// exchanges the authorization code and links the account to the address of the authorization
+pub async fn link_account(
+    state: &AppState,
+    provider: &OAuthProvider,
+    authorization: &Document,
+    code: &str,
+) -> Result<LinkedAccountDocument, String> {
+    let (Ok(address), Ok(code_verifier)) = (
+        authorization.get_str("address"),
+        authorization.get_str("code_verifier"),
+    ) else {
+        return Err("Invalid authorization".to_string());
+    };
+    let code_verifier = provider.pkce.then_some(code_verifier);
+    let redirect_uri = get_redirect_uri(state);
+    link_with_code(state, provider, address, code, &redirect_uri, code_verifier).await
+}
+
+// exchanges an authorization code requested with the redirect uri given and links the account
+// it grants access to to the address
+pub async fn link_with_code(
+    state: &AppState,
+    provider: &OAuthProvider,
+    address: &str,
+    code: &str,
+    redirect_uri: &str,
+    code_verifier: Option<&str>,
+) -> Result<LinkedAccountDocument, String> {
+    let mut params = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", redirect_uri),
+    ];
+    if let Some(code_verifier) = code_verifier {
+        params.push(("code_verifier", code_verifier));
+    }
+    let token = request_token(provider, &params).await?;
+    let access_token = token["access_token"].as_str().unwrap_or_default();
+    let (account_id, username) = get_identity(provider, access_token).await?;
+
+    let now = Utc::now().timestamp_millis();
+    let account = LinkedAccountDocument {
+        address: address.to_string(),
+        provider: provider.name.to_string(),
+        account_id,
+        username,
+        access_token: Some(access_token.to_string()),
+        refresh_token: token["refresh_token"].as_str().map(|s| s.to_string()),
+        expires_at: get_expiry(&token, now),
+        linked_at: now,
+    };
+    save_linked_account(state, &account).await?;
+    Ok(account)
+}
+
+// stores the account, relinking the same account keeping the time it was first linked at
+pub async fn save_linked_account(
+    state: &AppState,
+    account: &LinkedAccountDocument,
+) -> Result<(), String> {
+    let mut update = to_bson(account).map_err(|e| format!("Error serializing account: {}", e))?;
+    let linked_at = update.as_document_mut().and_then(|d| d.remove("linked_at"));
+    let filter = doc! {
+        "address": &account.address,
+        "provider": &account.provider,
+        "account_id": &account.account_id,
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    match state
+        .db
+        .collection::<Document>("linked_accounts")
+        .update_one(
+            filter,
+            doc! { "$set": update, "$setOnInsert": { "linked_at": linked_at } },
+            options,
+        )
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) if is_duplicate_key(&e) => Err(
+            "This account is already linked to another wallet, or your wallet to another account"
+                .to_string(),
+        ),
+        Err(e) => Err(format!("Error saving linked account: {}", e)),
+    }
+}
+
+pub async fn get_linked_account(
+    state: &AppState,
+    addr: FieldElement,
+    provider: &str,
+) -> Result<Option<LinkedAccountDocument>, String> {
+    state
+        .db
+        .collection::<LinkedAccountDocument>("linked_accounts")
+        .find_one(
+            doc! { "address": addr.to_string(), "provider": provider },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error querying linked account: {}", e))
+}
+
+pub async fn get_linked_accounts(
+    state: &AppState,
+    addr: FieldElement,
+) -> Result<Vec<LinkedAccountDocument>, String> {
+    state
+        .db
+        .collection::<LinkedAccountDocument>("linked_accounts")
+        .find(doc! { "address": addr.to_string() }, None)
+        .await
+        .map_err(|e| format!("Error querying linked accounts: {}", e))?
+        .try_collect()
+        .await
+        .map_err(|e| format!("Error querying linked accounts: {}", e))
+}
+
+// returns whether an account was unlinked
+pub async fn unlink_account(
+    state: &AppState,
+    addr: FieldElement,
+    provider: &str,
+) -> Result<bool, String> {
+    let result = state
+        .db
+        .collection::<Document>("linked_accounts")
+        .delete_one(
+            doc! { "address": addr.to_string(), "provider": provider },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error unlinking account: {}", e))?;
+    Ok(result.deleted_count > 0)
+}
+
+// records the task as completed by the social account for the address. An account completes each
+// task for one address only, so unlinking it and linking it to another wallet does not let it
+// complete the same tasks again
+pub async fn record_account_task(
+    state: &AppState,
+    account: &LinkedAccountDocument,
+    task_id: u32,
+) -> Result<(), String> {
+    let collection = state.db.collection::<Document>("account_completed_tasks");
+    let filter = doc! {
+        "provider": &account.provider,
+        "account_id": &account.account_id,
+        "task_id": task_id,
+    };
+    let mut completion = filter.clone();
+    completion.insert("address", &account.address);
+    completion.insert("timestamp", Utc::now().timestamp_millis());
+    match collection.insert_one(completion, None).await {
+        Ok(_) => return Ok(()),
+        Err(e) if is_duplicate_key(&e) => {}
+        Err(e) => return Err(format!("Error saving account task: {}", e)),
+    }
+    let completion = collection
+        .find_one(filter, None)
+        .await
+        .map_err(|e| format!("Error querying account task: {}", e))?
+        .ok_or_else(|| "Error querying account task".to_string())?;
+    if completion.get_str("address") == Ok(account.address.as_str()) {
+        Ok(())
+    } else {
+        Err("This account already completed this task with another wallet".to_string())
+    }
+}
+
+// access token of the account linked to the address, refreshed with its stored refresh token
+// once expired so that users only go through OAuth when linking their account
+pub async fn get_access_token(
+    state: &AppState,
+    addr: FieldElement,
+    provider_name: &str,
+) -> Result<(LinkedAccountDocument, String), String> {
+    let expired = "Your authorization expired, please link your account again".to_string();
+    let Some(account) = get_linked_account(state, addr, provider_name).await? else {
+        return Err("Please link your account first".to_string());
+    };
+    let Some(access_token) = account.access_token.clone() else {
+        return Err(expired);
+    };
+    let now = Utc::now().timestamp_millis();
+    match account.expires_at {
+        Some(expires_at) if expires_at <= now + TOKEN_EXPIRY_MARGIN_MS => {}
+        _ => return Ok((account, access_token)),
+    }
+
+    let (Some(provider), Some(refresh_token)) = (
+        get_oauth_provider(state, provider_name),
+        account.refresh_token.as_deref(),
+    ) else {
+        return Err(expired);
+    };
+    let params = [
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+    ];
+    let Ok(token) = request_token(&provider, &params).await else {
+        return Err(expired);
+    };
+    let access_token = token["access_token"].as_str().unwrap_or_default();
+
+    // refresh tokens may be rotated, the previous one being revoked
+    let mut update = doc! {
+        "access_token": access_token,
+        "expires_at": get_expiry(&token, now),
+    };
+    if let Some(refresh_token) = token["refresh_token"].as_str() {
+        update.insert("refresh_token", refresh_token);
+    }
+    state
+        .db
+        .collection::<Document>("linked_accounts")
+        .update_one(
+            doc! { "provider": provider_name, "account_id": &account.account_id },
+            doc! { "$set": update },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error saving linked account: {}", e))?;
+    Ok((account, access_token.to_string()))
+}
@@ -1,6 +1,23 @@
+pub mod achievement_claims;
+pub mod achievement_verifiers;
+pub mod block_sampling;
+pub mod boost_claims;
+pub mod boost_distribution;
+pub mod discord;
 pub mod get_achievement;
 pub mod has_deployed_time;
+pub mod leaderboard;
+pub mod linked_accounts;
+pub mod oauth_state;
+pub mod quest_completion;
+pub mod raffle;
+pub mod starknetid;
+pub mod telegram;
+pub mod twitter;
+pub mod verification_rules;
 pub mod verify_has_nft;
 pub mod verify_has_root_domain;
 pub mod verify_has_root_or_braavos_domain;
 pub mod verify_quiz;
+pub mod verify_signature;
+pub mod xp_ledger;
@@ -0,0 +1,201 @@
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use mongodb::{
+    bson::{doc, DateTime, Document},
+    options::IndexOptions,
+    Database, IndexModel,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::Sha256;
+
+use crate::{common::xp_ledger::is_duplicate_key, models::AppState, models::OAuthStatePayload};
+
+// a state must be sent back by the provider within 10 minutes
+const STATE_TTL_SECS: i64 = 600;
+
+// used nonces are kept until their state expires, after which the signature rejects it anyway
+pub async fn add_used_states_index(db: &Database) {
+    let ttl_options = IndexOptions::builder()
+        .expire_after(Duration::from_secs(0))
+        .build();
+    let ttl_index = IndexModel::builder()
+        .keys(doc! { "expires_at": 1 })
+        .options(ttl_options)
+        .build();
+    db.collection::<Document>("used_oauth_states")
+        .create_index(ttl_index, None)
+        .await
+        .unwrap();
+}
+
+fn get_mac(key: &[u8]) -> Hmac<Sha256> {
+    Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size")
+}
+
+fn encode_state(key: &[u8], payload: &OAuthStatePayload) -> Result<String, String> {
+    let payload =
+        serde_json::to_vec(payload).map_err(|e| format!("Error serializing state: {}", e))?;
+    let mut mac = get_mac(key);
+    mac.update(&payload);
+    let signature = mac.finalize().into_bytes();
+    Ok(format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(payload),
+        URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+// checks the signature and expiry of the state, its nonce being checked separately
+fn decode_state(key: &[u8], token: &str, now: i64) -> Result<OAuthStatePayload, String> {
+    let invalid = || "Invalid authorization state".to_string();
+    let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+    let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?;
+    let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+    let mut mac = get_mac(key);
+    mac.update(&payload);
+    mac.verify_slice(&signature).map_err(|_| invalid())?;
+
+    let payload: OAuthStatePayload = serde_json::from_slice(&payload).map_err(|_| invalid())?;
+    if payload.exp < now {
+        return Err("Your authorization expired, please try again".to_string());
+    }
+    Ok(payload)
+}
+
+// records the nonce of the state, failing if it was already used
+async fn consume_nonce(db: &Database, payload: &OAuthStatePayload) -> Result<(), String> {
+    let used_state = doc! {
+        "_id": &payload.nonce,
+        "expires_at": DateTime::from_millis(payload.exp * 1000),
+    };
+    match db
+        .collection::<Document>("used_oauth_states")
+        .insert_one(used_state, None)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) if is_duplicate_key(&e) => {
+            Err("This authorization was already used, please try again".to_string())
+        }
+        Err(e) => Err(format!("Error saving authorization state: {}", e)),
+    }
+}
+
+// state carrying the task being verified, signed so that it cannot be forged or altered
+pub fn sign_state(
+    state: &AppState,
+    addr: String,
+    quest_id: i64,
+    task_id: u32,
+) -> Result<String, String> {
+    let payload = OAuthStatePayload {
+        addr,
+        quest_id,
+        task_id,
+        exp: Utc::now().timestamp() + STATE_TTL_SECS,
+        nonce: rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(16)
+            .map(char::from)
+            .collect(),
+    };
+    encode_state(state.conf.auth.secret_key.as_bytes(), &payload)
+}
+
+// checks the signature and expiry of the state, and consumes its nonce so it is only used once
+pub async fn verify_state(state: &AppState, token: &str) -> Result<OAuthStatePayload, String> {
+    let payload = decode_state(
+        state.conf.auth.secret_key.as_bytes(),
+        token,
+        Utc::now().timestamp(),
+    )?;
+    consume_nonce(&state.db, &payload).await?;
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::Client;
+
+    const KEY: &[u8] = b"secret";
+
+    fn payload(exp: i64) -> OAuthStatePayload {
+        OAuthStatePayload {
+            addr: "1".to_string(),
+            quest_id: 9,
+            task_id: 39,
+            exp,
+            nonce: "nonce".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_decode_state() {
+        let token = encode_state(KEY, &payload(1_000)).unwrap();
+        let decoded = decode_state(KEY, &token, 900).unwrap();
+        assert_eq!((decoded.quest_id, decoded.task_id), (9, 39));
+        assert!(decode_state(b"other", &token, 900).is_err());
+    }
+
+    #[test]
+    fn test_decode_modified_payload() {
+        let token = encode_state(KEY, &payload(1_000)).unwrap();
+        let (_, signature) = token.split_once('.').unwrap();
+        let mut modified = payload(1_000);
+        modified.task_id = 80;
+        let modified = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&modified).unwrap());
+        assert_eq!(
+            decode_state(KEY, &format!("{}.{}", modified, signature), 900).unwrap_err(),
+            "Invalid authorization state"
+        );
+    }
+
+    #[test]
+    fn test_decode_modified_signature() {
+        let token = encode_state(KEY, &payload(1_000)).unwrap();
+        let (encoded, signature) = token.split_once('.').unwrap();
+        let mut signature = URL_SAFE_NO_PAD.decode(signature).unwrap();
+        signature[0] ^= 1;
+        let token = format!("{}.{}", encoded, URL_SAFE_NO_PAD.encode(signature));
+        assert_eq!(
+            decode_state(KEY, &token, 900).unwrap_err(),
+            "Invalid authorization state"
+        );
+        assert!(decode_state(KEY, encoded, 900).is_err());
+    }
+
+    #[test]
+    fn test_decode_expired_state() {
+        let token = encode_state(KEY, &payload(1_000)).unwrap();
+        assert!(decode_state(KEY, &token, 1_000).is_ok());
+        assert_eq!(
+            decode_state(KEY, &token, 1_001).unwrap_err(),
+            "Your authorization expired, please try again"
+        );
+    }
+
+    // needs a running mongodb: MONGODB_TEST_URI=mongodb://localhost:27017 cargo test -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn reused_nonce_is_rejected() {
+        let uri = std::env::var("MONGODB_TEST_URI")
+            .unwrap_or_else(|_| "mongodb://localhost:27017".to_string());
+        let db = Client::with_uri_str(&uri).await.unwrap().database(&format!(
+            "oauth_state_test_{}",
+            Utc::now().timestamp_millis()
+        ));
+        add_used_states_index(&db).await;
+
+        let payload = payload(Utc::now().timestamp() + STATE_TTL_SECS);
+        assert!(consume_nonce(&db, &payload).await.is_ok());
+        assert_eq!(
+            consume_nonce(&db, &payload).await.unwrap_err(),
+            "This authorization was already used, please try again"
+        );
+        db.drop(None).await.unwrap();
+    }
+}
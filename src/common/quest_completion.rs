@@ -0,0 +1,402 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use mongodb::{
+    bson::{doc, to_bson, Bson, Document},
+    error::Error,
+    options::{IndexOptions, UpdateOptions},
+    results::UpdateResult,
+    Database, IndexModel,
+};
+use starknet::core::types::FieldElement;
+
+use crate::{
+    common::xp_ledger::{credit_experience, is_duplicate_key},
+    models::{BlockSample, XpSource},
+};
+
+// a task and a quest are completed at most once per address, which the unique indexes enforce.
+// Duplicated completed tasks must be removed first, see migrations/001_quest_completions.js, so
+// a failure is returned for the server to report rather than stopping it
+pub async fn add_quest_completions_index(db: &Database) -> Result<(), String> {
+    let unique_options = IndexOptions::builder().unique(true).build();
+    let task_index = IndexModel::builder()
+        .keys(doc! { "address": 1, "task_id": 1 })
+        .options(unique_options.clone())
+        .build();
+    let task_result = db
+        .collection::<Document>("completed_tasks")
+        .create_index(task_index, None)
+        .await;
+    let completion_index = IndexModel::builder()
+        .keys(doc! { "address": 1, "quest_id": 1 })
+        .options(unique_options)
+        .build();
+    db.collection::<Document>("quest_completions")
+        .create_index(completion_index, None)
+        .await
+        .map_err(|e| format!("Error creating quest completions index: {}", e))?;
+    task_result.map(|_| ()).map_err(|e| {
+        format!(
+            "Error creating completed tasks index, run migrations/001_quest_completions.js: {}",
+            e
+        )
+    })
+}
+
+fn get_id(document: &Document, field: &str) -> Option<i64> {
+    match document.get(field) {
+        Some(Bson::Int32(value)) => Some(*value as i64),
+        Some(Bson::Int64(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+fn get_bson_id(value: &Bson) -> Option<i64> {
+    match value {
+        Bson::Int32(value) => Some(*value as i64),
+        Bson::Int64(value) => Some(*value),
+        _ => None,
+    }
+}
+
+// whether every task of the quest is among the completed ones, duplicated completions of a task
+// not making up for a missing one
+fn has_completed_all(task_ids: &[Bson], done: &[Bson]) -> bool {
+    let done: Vec<i64> = done.iter().filter_map(get_bson_id).collect();
+    !task_ids.is_empty()
+        && task_ids
+            .iter()
+            .all(|id| get_bson_id(id).map_or(false, |id| done.contains(&id)))
+}
+
+// storage of the completion flow, the unique indexes of the database being what keeps concurrent
+// calls from crediting a quest twice
+#[async_trait]
+trait CompletionStore {
+    // an existing completion is left as is
+    async fn insert_completion(&self, address: &str, quest_id: i64) -> Result<(), Error>;
+    async fn is_uncredited(&self, address: &str, quest_id: i64) -> Result<bool, Error>;
+    // false when the ledger already has the entry of the quest
+    async fn credit(&self, address: &str, quest_id: i64, experience: i64) -> Result<bool, Error>;
+    async fn flag_credited(&self, address: &str, quest_id: i64) -> Result<(), Error>;
+}
+
+#[async_trait]
+impl CompletionStore for Database {
+    async fn insert_completion(&self, address: &str, quest_id: i64) -> Result<(), Error> {
+        let completion = doc! {
+            "address": address,
+            "quest_id": quest_id,
+            "timestamp": Utc::now().timestamp_millis(),
+            "credited": false,
+        };
+        match self
+            .collection::<Document>("quest_completions")
+            .insert_one(completion, None)
+            .await
+        {
+            Err(e) if !is_duplicate_key(&e) => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    async fn is_uncredited(&self, address: &str, quest_id: i64) -> Result<bool, Error> {
+        let filter = doc! { "address": address, "quest_id": quest_id, "credited": false };
+        Ok(self
+            .collection::<Document>("quest_completions")
+            .find_one(filter, None)
+            .await?
+            .is_some())
+    }
+
+    async fn credit(&self, address: &str, quest_id: i64, experience: i64) -> Result<bool, Error> {
+        let source = XpSource::Quest {
+            quest_id: quest_id as u32,
+        };
+        match credit_experience(self, address.to_string(), experience, source).await {
+            Ok(_) => Ok(true),
+            Err(e) if is_duplicate_key(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn flag_credited(&self, address: &str, quest_id: i64) -> Result<(), Error> {
+        self.collection::<Document>("quest_completions")
+            .update_one(
+                doc! { "address": address, "quest_id": quest_id, "credited": false },
+                doc! { "$set": { "credited": true } },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// the completion is recorded first and flagged once credited, a completion left uncredited by a
+// failure being credited again on the next call. The ledger accepting a single entry per quest
+// and address, concurrent calls credit it once. Returns whether this call did
+async fn credit_completion(
+    store: &(impl CompletionStore + Sync),
+    address: &str,
+    quest_id: i64,
+    experience: i64,
+) -> Result<bool, Error> {
+    store.insert_completion(address, quest_id).await?;
+    if !store.is_uncredited(address, quest_id).await? {
+        return Ok(false);
+    }
+    let credited = store.credit(address, quest_id, experience).await?;
+    store.flag_credited(address, quest_id).await?;
+    Ok(credited)
+}
+
+// records the quest of the task as completed once all of its tasks are done and credits its
+// experience, see credit_completion
+pub async fn credit_quest_completion(
+    db: &Database,
+    address: &str,
+    task_id: u32,
+) -> Result<bool, Error> {
+    let tasks_collection = db.collection::<Document>("tasks");
+    let Some(quest_id) = tasks_collection
+        .find_one(doc! { "id": task_id }, None)
+        .await?
+        .and_then(|task| get_id(&task, "quest_id"))
+    else {
+        return Ok(false);
+    };
+
+    let task_ids: Vec<Bson> = tasks_collection
+        .distinct("id", doc! { "quest_id": quest_id }, None)
+        .await?;
+    let done = db
+        .collection::<Document>("completed_tasks")
+        .distinct(
+            "task_id",
+            doc! { "address": address, "task_id": { "$in": &task_ids } },
+            None,
+        )
+        .await?;
+    if !has_completed_all(&task_ids, &done) {
+        return Ok(false);
+    }
+
+    let experience = db
+        .collection::<Document>("quests")
+        .find_one(doc! { "id": quest_id }, None)
+        .await?
+        .and_then(|quest| get_id(&quest, "experience"))
+        .unwrap_or(0);
+    if experience == 0 {
+        return Ok(false);
+    }
+    credit_completion(db, address, quest_id, experience).await
+}
+
+pub async fn record_completed_task(
+    db: &Database,
+    addr: FieldElement,
+    task_id: u32,
+    block_samples: Vec<BlockSample>,
+) -> Result<UpdateResult, Error> {
+    let created_at = Utc::now().timestamp_millis();
+    let filter = doc! { "address": addr.to_string(), "task_id": task_id };
+    let mut completed_task =
+        doc! { "address": addr.to_string(), "task_id": task_id, "timestamp": created_at };
+    // blocks the on-chain condition was checked at, kept for audit
+    if !block_samples.is_empty() {
+        completed_task.insert("block_samples", to_bson(&block_samples)?);
+    }
+    let update = doc! { "$setOnInsert": completed_task };
+    let options = UpdateOptions::builder().upsert(true).build();
+
+    // concurrent upserts of the same task may lose against the unique index, matching the
+    // completed task when tried again
+    let collection = db.collection::<Document>("completed_tasks");
+    let result = match collection
+        .update_one(filter.clone(), update.clone(), options.clone())
+        .await
+    {
+        Err(e) if is_duplicate_key(&e) => collection.update_one(filter, update, options).await?,
+        result => result?,
+    };
+
+    // tried on every verification so that a quest left uncredited is credited on the next one
+    credit_quest_completion(db, &addr.to_string(), task_id).await?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::xp_ledger::add_ledger_indexes;
+    use futures::future::join_all;
+    use mongodb::Client;
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    // keeps the unique keys of quest_completions and of the ledger like the database indexes do
+    #[derive(Default)]
+    struct MemoryStore {
+        completions: Mutex<HashMap<(String, i64), bool>>,
+        ledger: Mutex<HashSet<(String, i64)>>,
+        credits: AtomicUsize,
+        failures_left: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CompletionStore for MemoryStore {
+        async fn insert_completion(&self, address: &str, quest_id: i64) -> Result<(), Error> {
+            let mut completions = self.completions.lock().unwrap();
+            completions
+                .entry((address.to_string(), quest_id))
+                .or_insert(false);
+            Ok(())
+        }
+
+        async fn is_uncredited(&self, address: &str, quest_id: i64) -> Result<bool, Error> {
+            let completions = self.completions.lock().unwrap();
+            Ok(completions.get(&(address.to_string(), quest_id)) == Some(&false))
+        }
+
+        async fn credit(&self, address: &str, quest_id: i64, _: i64) -> Result<bool, Error> {
+            tokio::task::yield_now().await;
+            if self
+                .failures_left
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                return Err(std::io::Error::new(std::io::ErrorKind::Other, "ledger down").into());
+            }
+            let inserted = self
+                .ledger
+                .lock()
+                .unwrap()
+                .insert((address.to_string(), quest_id));
+            if inserted {
+                self.credits.fetch_add(1, Ordering::SeqCst);
+            }
+            Ok(inserted)
+        }
+
+        async fn flag_credited(&self, address: &str, quest_id: i64) -> Result<(), Error> {
+            let mut completions = self.completions.lock().unwrap();
+            completions.insert((address.to_string(), quest_id), true);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_has_completed_all() {
+        let tasks = [Bson::Int32(1), Bson::Int32(2)];
+        assert!(has_completed_all(&tasks, &[Bson::Int64(2), Bson::Int32(1)]));
+        // a duplicated completion does not make up for a missing task
+        assert!(!has_completed_all(
+            &tasks,
+            &[Bson::Int32(1), Bson::Int32(1)]
+        ));
+        assert!(!has_completed_all(&[], &[]));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_completions_credit_once() {
+        let store = Arc::new(MemoryStore::default());
+        let calls = (0..16).map(|_| {
+            let store = store.clone();
+            tokio::spawn(async move { credit_completion(store.as_ref(), "0x1", 1, 50).await })
+        });
+        let credited = join_all(calls)
+            .await
+            .into_iter()
+            .filter(|result| *result.as_ref().unwrap().as_ref().unwrap())
+            .count();
+        assert_eq!(credited, 1);
+        assert_eq!(store.credits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_credit_is_retried() {
+        let store = MemoryStore {
+            failures_left: AtomicUsize::new(1),
+            ..Default::default()
+        };
+        assert!(credit_completion(&store, "0x1", 1, 50).await.is_err());
+        assert!(store.is_uncredited("0x1", 1).await.unwrap());
+        assert!(credit_completion(&store, "0x1", 1, 50).await.unwrap());
+        assert!(!credit_completion(&store, "0x1", 1, 50).await.unwrap());
+        assert_eq!(store.credits.load(Ordering::SeqCst), 1);
+    }
+
+    // needs a running mongodb: MONGODB_TEST_URI=mongodb://localhost:27017 cargo test -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn concurrent_verifications_credit_once() {
+        let uri = std::env::var("MONGODB_TEST_URI")
+            .unwrap_or_else(|_| "mongodb://localhost:27017".to_string());
+        let db = Client::with_uri_str(&uri).await.unwrap().database(&format!(
+            "quest_completion_test_{}",
+            Utc::now().timestamp_millis()
+        ));
+        add_quest_completions_index(&db).await.unwrap();
+        add_ledger_indexes(&db).await;
+        db.collection::<Document>("quests")
+            .insert_one(doc! { "id": 1, "experience": 50 }, None)
+            .await
+            .unwrap();
+        let tasks: Vec<Document> = (1..=4).map(|id| doc! { "id": id, "quest_id": 1 }).collect();
+        db.collection::<Document>("tasks")
+            .insert_many(tasks, None)
+            .await
+            .unwrap();
+
+        // every task of every address is verified twice, all at the same time
+        let addresses: Vec<FieldElement> = (1..=20u64).map(FieldElement::from).collect();
+        let mut verifications = Vec::new();
+        for addr in &addresses {
+            for task_id in (1..=4).chain(1..=4) {
+                let (db, addr) = (db.clone(), *addr);
+                verifications.push(tokio::spawn(async move {
+                    record_completed_task(&db, addr, task_id, vec![]).await
+                }));
+            }
+        }
+        for result in join_all(verifications).await {
+            result.unwrap().unwrap();
+        }
+
+        for addr in &addresses {
+            let address = addr.to_string();
+            let tasks = db
+                .collection::<Document>("completed_tasks")
+                .count_documents(doc! { "address": &address }, None)
+                .await
+                .unwrap();
+            assert_eq!(tasks, 4);
+            let completions = db
+                .collection::<Document>("quest_completions")
+                .count_documents(doc! { "address": &address }, None)
+                .await
+                .unwrap();
+            assert_eq!(completions, 1);
+            let entries = db
+                .collection::<Document>("user_exp")
+                .count_documents(doc! { "address": &address }, None)
+                .await
+                .unwrap();
+            assert_eq!(entries, 1);
+            let leaderboard = db
+                .collection::<Document>("leaderboard_table")
+                .find_one(doc! { "_id": &address }, None)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(get_id(&leaderboard, "experience"), Some(50));
+        }
+        db.drop(None).await.unwrap();
+    }
+}
@@ -0,0 +1,237 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Bson, Document};
+use num_bigint::BigUint;
+use starknet::{
+    core::{
+        crypto::pedersen_hash,
+        types::{BlockId, FieldElement},
+    },
+    providers::Provider,
+};
+
+use crate::{
+    common::{
+        block_sampling::{get_block_hash, get_block_timestamp, get_first_block_after},
+        verify_has_root_domain::get_root_domain_expiry,
+    },
+    models::{AppState, BoostWeighting},
+};
+
+// a new version must be added whenever a draw changes so that older proofs stay reproducible
+pub const RAFFLE_ALGORITHM_VERSION: u32 = 1;
+pub const WEIGHTED_RAFFLE_ALGORITHM_VERSION: u32 = 2;
+
+// holders of a root domain get this many tickets instead of one
+const ROOT_DOMAIN_WEIGHT: u64 = 2;
+
+// the seed is the hash of the first block produced at or after the boost expiry (in ms),
+// None is returned while that block does not exist yet
+pub async fn get_raffle_seed(
+    state: &AppState,
+    expiry: i64,
+) -> Result<Option<(u64, FieldElement)>, String> {
+    let expiry_timestamp = (expiry.max(0) as u64 + 999) / 1000;
+    let latest_block = state
+        .provider
+        .block_number()
+        .await
+        .map_err(|e| format!("Error querying block number: {}", e))?;
+    let latest_timestamp = get_block_timestamp(state, BlockId::Number(latest_block)).await?;
+    if latest_timestamp < expiry_timestamp {
+        return Ok(None);
+    }
+
+    let block_number =
+        get_first_block_after(state, expiry_timestamp, latest_block, latest_timestamp).await?;
+    let block_hash = get_block_hash(state, BlockId::Number(block_number)).await?;
+    Ok(block_hash.map(|block_hash| (block_number, block_hash)))
+}
+
+// sorts and dedupes the participants, then runs a partial Fisher-Yates shuffle where
+// the i-th winner is swapped in from index i + pedersen(seed, i) mod (n - i)
+pub fn draw_winners(
+    participants: &[FieldElement],
+    seed: FieldElement,
+    num_of_winners: usize,
+) -> Vec<FieldElement> {
+    let mut pool = participants.to_vec();
+    pool.sort_by_key(|felt| felt.to_bytes_be());
+    pool.dedup();
+
+    let num_of_winners = num_of_winners.min(pool.len());
+    for i in 0..num_of_winners {
+        let hash = pedersen_hash(&seed, &FieldElement::from(i as u64));
+        let offset = BigUint::from_bytes_be(&hash.to_bytes_be()) % (pool.len() - i);
+        let offset = offset.to_u64_digits().first().copied().unwrap_or(0) as usize;
+        pool.swap(i, i + offset);
+    }
+    pool.truncate(num_of_winners);
+    pool
+}
+
+// participants must be sorted and deduped, entries holds one address per completed quest
+pub async fn get_raffle_weights(
+    state: &AppState,
+    weighting: &BoostWeighting,
+    participants: &[FieldElement],
+    entries: &[FieldElement],
+) -> Result<Vec<u64>, String> {
+    match weighting {
+        BoostWeighting::Uniform => Ok(vec![1; participants.len()]),
+        BoostWeighting::QuestCount => Ok(participants
+            .iter()
+            .map(|addr| entries.iter().filter(|entry| *entry == addr).count() as u64)
+            .collect()),
+        BoostWeighting::Experience => {
+            let leaderboard_collection = state.db.collection::<Document>("leaderboard_table");
+            let addresses: Vec<String> = participants.iter().map(|addr| addr.to_string()).collect();
+            let mut cursor = leaderboard_collection
+                .find(doc! { "_id": { "$in": &addresses } }, None)
+                .await
+                .map_err(|e| format!("Error querying experience: {}", e))?;
+            let mut weights = vec![1; participants.len()];
+            while let Some(doc) = cursor
+                .try_next()
+                .await
+                .map_err(|e| format!("Error querying experience: {}", e))?
+            {
+                // experience is summed by the view, so its bson type depends on the inputs
+                let experience = match doc.get("experience") {
+                    Some(Bson::Int32(experience)) => *experience as i64,
+                    Some(Bson::Int64(experience)) => *experience,
+                    Some(Bson::Double(experience)) => *experience as i64,
+                    _ => continue,
+                };
+                let Ok(address) = doc.get_str("_id") else {
+                    continue;
+                };
+                if let Some(index) = addresses.iter().position(|addr| addr == address) {
+                    // everyone eligible keeps at least one ticket
+                    weights[index] = experience.max(1) as u64;
+                }
+            }
+            Ok(weights)
+        }
+        BoostWeighting::RootDomain => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|_| "system time before UNIX EPOCH".to_string())?
+                .as_secs();
+            let mut weights = vec![];
+            for addr in participants {
+                weights.push(match get_root_domain_expiry(state, addr).await? {
+                    Some(expiry) if expiry >= now => ROOT_DOMAIN_WEIGHT,
+                    _ => 1,
+                });
+            }
+            Ok(weights)
+        }
+    }
+}
+
+// participants are taken in snapshot order, then each winner is picked with a probability
+// proportional to its weight, pedersen(seed, i) mod total_weight selecting the i-th one
+pub fn draw_weighted_winners(
+    participants: &[FieldElement],
+    weights: &[u64],
+    seed: FieldElement,
+    num_of_winners: usize,
+) -> Result<Vec<FieldElement>, String> {
+    if participants.len() != weights.len() {
+        return Err("Participants and weights lengths differ".to_string());
+    }
+    let mut pool: Vec<(FieldElement, u64)> = participants
+        .iter()
+        .copied()
+        .zip(weights.iter().copied())
+        .filter(|(_, weight)| *weight > 0)
+        .collect();
+
+    let mut winners = vec![];
+    for i in 0..num_of_winners.min(pool.len()) {
+        let total: u64 = pool.iter().map(|(_, weight)| weight).sum();
+        let hash = pedersen_hash(&seed, &FieldElement::from(i as u64));
+        let target = BigUint::from_bytes_be(&hash.to_bytes_be()) % total;
+        let mut target = target.to_u64_digits().first().copied().unwrap_or(0);
+        let index = pool
+            .iter()
+            .position(|(_, weight)| {
+                if target < *weight {
+                    return true;
+                }
+                target -= weight;
+                false
+            })
+            .unwrap_or(pool.len() - 1);
+        winners.push(pool.remove(index).0);
+    }
+    Ok(winners)
+}
+
+// replays the draw of the given algorithm version
+pub fn run_raffle(
+    algorithm_version: u32,
+    participants: &[FieldElement],
+    weights: Option<&[u64]>,
+    seed: FieldElement,
+    num_of_winners: usize,
+) -> Result<Vec<FieldElement>, String> {
+    match (algorithm_version, weights) {
+        (RAFFLE_ALGORITHM_VERSION, None) => Ok(draw_winners(participants, seed, num_of_winners)),
+        (WEIGHTED_RAFFLE_ALGORITHM_VERSION, Some(weights)) => {
+            draw_weighted_winners(participants, weights, seed, num_of_winners)
+        }
+        _ => Err(format!(
+            "Unsupported raffle algorithm version {}",
+            algorithm_version
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn participants(count: u64) -> Vec<FieldElement> {
+        (1..=count).map(FieldElement::from).collect()
+    }
+
+    #[test]
+    fn test_draw_is_deterministic() {
+        let seed = FieldElement::from(123456789_u64);
+        let mut shuffled = participants(50);
+        shuffled.reverse();
+        let winners = draw_winners(&participants(50), seed, 10);
+        assert_eq!(winners.len(), 10);
+        assert_eq!(winners, draw_winners(&shuffled, seed, 10));
+        assert_ne!(
+            winners,
+            draw_winners(&participants(50), FieldElement::ONE, 10)
+        );
+    }
+
+    #[test]
+    fn test_draw_winners_are_unique() {
+        let mut with_duplicates = participants(5);
+        with_duplicates.extend(participants(5));
+        let mut winners = draw_winners(&with_duplicates, FieldElement::from(2_u64), 10);
+        assert_eq!(winners.len(), 5);
+        winners.sort_by_key(|felt| felt.to_bytes_be());
+        assert_eq!(winners, participants(5));
+    }
+
+    #[test]
+    fn test_weighted_draw() {
+        let seed = FieldElement::from(42_u64);
+        let winners = draw_weighted_winners(&participants(3), &[0, 5, 1], seed, 3).unwrap();
+        assert_eq!(winners.len(), 2);
+        assert!(!winners.contains(&FieldElement::ONE));
+        assert_eq!(
+            winners,
+            draw_weighted_winners(&participants(3), &[0, 5, 1], seed, 3).unwrap()
+        );
+        assert!(draw_weighted_winners(&participants(3), &[1], seed, 1).is_err());
+    }
+}
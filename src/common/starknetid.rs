@@ -0,0 +1,251 @@
+use std::{collections::HashMap, str::FromStr, time::Duration};
+
+use chrono::Utc;
+use futures::{stream, StreamExt, TryStreamExt};
+use mongodb::{
+    bson::{doc, DateTime, Document},
+    options::{IndexOptions, UpdateOptions},
+    Database, IndexModel,
+};
+use num_bigint::BigUint;
+use starknet::{
+    core::types::{BlockId, BlockTag, FieldElement, FunctionCall},
+    macros::{selector, short_string},
+    providers::Provider,
+};
+use starknet_id::decode;
+
+use crate::{
+    models::{AppState, StarknetIdProfile},
+    utils::to_hex,
+};
+
+// resolved profiles are refreshed after an hour, expired entries being dropped by mongo
+const PROFILE_CACHE_TTL_MS: i64 = 3_600_000;
+// profiles resolved at once, each one taking up to four rpc calls
+const MAX_CONCURRENT_RESOLUTIONS: usize = 8;
+
+pub async fn add_profile_cache_index(db: &Database) {
+    let ttl_options = IndexOptions::builder()
+        .expire_after(Duration::from_secs(0))
+        .build();
+    let ttl_index = IndexModel::builder()
+        .keys(doc! { "expires_at": 1 })
+        .options(ttl_options)
+        .build();
+    db.collection::<Document>("starknetid_profiles")
+        .create_index(ttl_index, None)
+        .await
+        .unwrap();
+}
+
+async fn call(
+    state: &AppState,
+    contract: FieldElement,
+    entry_point: FieldElement,
+    calldata: Vec<FieldElement>,
+) -> Result<Vec<FieldElement>, String> {
+    state
+        .provider
+        .call(
+            FunctionCall {
+                contract_address: contract,
+                entry_point_selector: entry_point,
+                calldata,
+            },
+            BlockId::Tag(BlockTag::Latest),
+        )
+        .await
+        .map_err(|e| format!("{}", e))
+}
+
+// reads the nft set as profile picture of the identity the domain points to
+async fn resolve_pfp(
+    state: &AppState,
+    domain_calldata: Vec<FieldElement>,
+) -> Result<Option<(String, String)>, String> {
+    let Some(pfp_verifier) = state.conf.starknetid_contracts.pfp_verifier_contract else {
+        return Ok(None);
+    };
+    let contracts = &state.conf.starknetid_contracts;
+    let id = call(
+        state,
+        contracts.naming_contract,
+        selector!("domain_to_id"),
+        domain_calldata,
+    )
+    .await?;
+    let Some(id) = id.first() else {
+        return Ok(None);
+    };
+
+    let nft_contract = call(
+        state,
+        contracts.identity_contract,
+        selector!("get_verifier_data"),
+        vec![
+            *id,
+            short_string!("nft_pp_contract"),
+            pfp_verifier,
+            FieldElement::ZERO,
+        ],
+    )
+    .await?;
+    let nft_contract = match nft_contract.first() {
+        Some(contract) if *contract != FieldElement::ZERO => *contract,
+        _ => return Ok(None),
+    };
+
+    // token ids are stored as an u256, returned as [len, low, high]
+    let nft_id = call(
+        state,
+        contracts.identity_contract,
+        selector!("get_extended_verifier_data"),
+        vec![
+            *id,
+            short_string!("nft_pp_id"),
+            FieldElement::TWO,
+            pfp_verifier,
+        ],
+    )
+    .await?;
+    if nft_id.len() < 3 {
+        return Ok(None);
+    }
+    let nft_id = (BigUint::from_bytes_be(&nft_id[2].to_bytes_be()) << 128)
+        + BigUint::from_bytes_be(&nft_id[1].to_bytes_be());
+    Ok(Some((to_hex(nft_contract), nft_id.to_string())))
+}
+
+async fn resolve_profile(
+    state: &AppState,
+    addr: FieldElement,
+) -> Result<StarknetIdProfile, String> {
+    let mut profile = StarknetIdProfile {
+        address: to_hex(addr),
+        domain: None,
+        pfp_contract: None,
+        pfp_id: None,
+    };
+
+    // the domain is returned as [len, label...], subdomain labels coming first
+    let domain = call(
+        state,
+        state.conf.starknetid_contracts.naming_contract,
+        selector!("address_to_domain"),
+        vec![addr, FieldElement::ZERO],
+    )
+    .await?;
+    if domain.len() < 2 || domain[0] == FieldElement::ZERO {
+        return Ok(profile);
+    }
+    let labels: Vec<String> = domain[1..].iter().map(|label| decode(*label)).collect();
+    profile.domain = Some(format!("{}.stark", labels.join(".")));
+
+    if let Some((pfp_contract, pfp_id)) = resolve_pfp(state, domain).await? {
+        profile.pfp_contract = Some(pfp_contract);
+        profile.pfp_id = Some(pfp_id);
+    }
+    Ok(profile)
+}
+
+// resolves a batch of addresses, keyed by the address strings given. Cached profiles are
+// reused and the others resolved a few at a time, addresses failing to resolve having no domain
+pub async fn get_profiles(
+    state: &AppState,
+    addresses: &[String],
+) -> HashMap<String, StarknetIdProfile> {
+    let collection = state.db.collection::<Document>("starknetid_profiles");
+    let felts: HashMap<String, FieldElement> = addresses
+        .iter()
+        .filter_map(|addr| {
+            FieldElement::from_str(addr)
+                .ok()
+                .map(|felt| (addr.clone(), felt))
+        })
+        .collect();
+    let mut hexes: Vec<String> = felts.values().map(|felt| to_hex(*felt)).collect();
+    hexes.sort();
+    hexes.dedup();
+
+    let mut resolved: HashMap<String, StarknetIdProfile> = HashMap::new();
+    let now = Utc::now().timestamp_millis();
+    let filter = doc! {
+        "address": { "$in": &hexes },
+        "expires_at": { "$gt": DateTime::from_millis(now) },
+    };
+    if let Ok(cursor) = collection.find(filter, None).await {
+        let cached: Vec<Document> = cursor.try_collect().await.unwrap_or_default();
+        for entry in cached {
+            let get = |field: &str| entry.get_str(field).ok().map(|value| value.to_string());
+            if let Some(address) = get("address") {
+                let profile = StarknetIdProfile {
+                    address: address.clone(),
+                    domain: get("domain"),
+                    pfp_contract: get("pfp_contract"),
+                    pfp_id: get("pfp_id"),
+                };
+                resolved.insert(address, profile);
+            }
+        }
+    }
+
+    let missing: Vec<FieldElement> = hexes
+        .iter()
+        .filter(|hex| !resolved.contains_key(*hex))
+        .filter_map(|hex| FieldElement::from_hex_be(hex).ok())
+        .collect();
+    let results: Vec<Result<StarknetIdProfile, String>> = stream::iter(missing)
+        .map(|addr| resolve_profile(state, addr))
+        .buffer_unordered(MAX_CONCURRENT_RESOLUTIONS)
+        .collect()
+        .await;
+    let expires_at = DateTime::from_millis(now + PROFILE_CACHE_TTL_MS);
+    for result in results {
+        // failed resolutions are not cached so that they are retried on the next request
+        let profile = match result {
+            Ok(profile) => profile,
+            Err(e) => {
+                state
+                    .logger
+                    .warning(format!("Error resolving starknet id profile: {}", e));
+                continue;
+            }
+        };
+        let options = UpdateOptions::builder().upsert(true).build();
+        let update = doc! {
+            "$set": {
+                "domain": &profile.domain,
+                "pfp_contract": &profile.pfp_contract,
+                "pfp_id": &profile.pfp_id,
+                "expires_at": expires_at,
+            }
+        };
+        if let Err(e) = collection
+            .update_one(doc! { "address": &profile.address }, update, options)
+            .await
+        {
+            state
+                .logger
+                .warning(format!("Error caching starknet id profile: {}", e));
+        }
+        resolved.insert(profile.address.clone(), profile);
+    }
+
+    felts
+        .into_iter()
+        .map(|(addr, felt)| {
+            let mut profile = resolved
+                .get(&to_hex(felt))
+                .cloned()
+                .unwrap_or(StarknetIdProfile {
+                    address: String::new(),
+                    domain: None,
+                    pfp_contract: None,
+                    pfp_id: None,
+                });
+            profile.address = addr.clone();
+            (addr, profile)
+        })
+        .collect()
+}
@@ -0,0 +1,216 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use mongodb::bson::doc;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use starknet::core::types::FieldElement;
+
+use crate::{
+    common::linked_accounts::{get_linked_account, record_account_task, save_linked_account},
+    models::{AppState, LinkedAccountDocument, QuestTaskDocument, TelegramAuthData},
+    utils::CompletedTasksTrait,
+};
+
+const API_URL: &str = "https://api.telegram.org";
+// a login must be sent within a day of the user authorizing it
+const AUTH_MAX_AGE_SECS: i64 = 86_400;
+
+fn get_bot_token(state: &AppState) -> Result<&str, String> {
+    state
+        .conf
+        .telegram
+        .as_ref()
+        .map(|telegram| telegram.bot_token.as_str())
+        .ok_or_else(|| "Telegram is not configured".to_string())
+}
+
+// checks the data was signed by telegram for the bot, the key being the sha256 of its token
+// and the signed string the sorted key=value lines of every field but the hash
+fn verify_login(bot_token: &str, data: &TelegramAuthData, now: i64) -> Result<(), String> {
+    let mut fields = vec![
+        format!("auth_date={}", data.auth_date),
+        format!("id={}", data.id),
+    ];
+    let optional_fields = [
+        ("first_name", &data.first_name),
+        ("last_name", &data.last_name),
+        ("username", &data.username),
+        ("photo_url", &data.photo_url),
+    ];
+    for (key, value) in optional_fields {
+        if let Some(value) = value {
+            fields.push(format!("{}={}", key, value));
+        }
+    }
+    fields.sort();
+
+    let invalid = || "Invalid Telegram login".to_string();
+    let hash = hex::decode(&data.hash).map_err(|_| invalid())?;
+    let secret_key = Sha256::digest(bot_token.as_bytes());
+    let mut mac = Hmac::<Sha256>::new_from_slice(&secret_key).map_err(|_| invalid())?;
+    mac.update(fields.join("\n").as_bytes());
+    mac.verify_slice(&hash).map_err(|_| invalid())?;
+
+    if now - data.auth_date > AUTH_MAX_AGE_SECS {
+        return Err("Your Telegram login expired, please try again".to_string());
+    }
+    Ok(())
+}
+
+// links the telegram account of a login widget authorization to the address
+pub async fn link_account(
+    state: &AppState,
+    addr: FieldElement,
+    data: &TelegramAuthData,
+) -> Result<LinkedAccountDocument, String> {
+    verify_login(get_bot_token(state)?, data, Utc::now().timestamp())?;
+    let username = data
+        .username
+        .clone()
+        .or_else(|| data.first_name.clone())
+        .unwrap_or_default();
+    let account = LinkedAccountDocument {
+        address: addr.to_string(),
+        provider: "telegram".to_string(),
+        account_id: data.id.to_string(),
+        username,
+        access_token: None,
+        refresh_token: None,
+        expires_at: None,
+        linked_at: Utc::now().timestamp_millis(),
+    };
+    save_linked_account(state, &account).await?;
+    Ok(account)
+}
+
+// whether the user is in the chat, which the bot can only tell if it is a member of it
+async fn is_chat_member(bot_token: &str, chat_id: &str, user_id: &str) -> Result<bool, String> {
+    let json: Value = reqwest::Client::new()
+        .get(format!("{}/bot{}/getChatMember", API_URL, bot_token))
+        .query(&[("chat_id", chat_id), ("user_id", user_id)])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to get member info: {}", e))?
+        .json()
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to get JSON response while fetching member info: {}",
+                e
+            )
+        })?;
+    // users that never joined the chat are reported as an error, any other one being a problem
+    // with the bot or the chat of the task
+    if json["ok"].as_bool() != Some(true) {
+        let description = json["description"]
+            .as_str()
+            .unwrap_or("Failed to get member info");
+        if description.to_lowercase().contains("user not found") {
+            return Ok(false);
+        }
+        return Err(format!("Telegram returned an error: {}", description));
+    }
+    let member = &json["result"];
+    Ok(match member["status"].as_str() {
+        Some("creator") | Some("administrator") | Some("member") => true,
+        Some("restricted") => member["is_member"].as_bool().unwrap_or(false),
+        _ => false,
+    })
+}
+
+// verifies a telegram task of the quest with the account linked to the address and records it
+// as completed
+pub async fn verify_task(
+    state: &AppState,
+    addr: FieldElement,
+    quest_id: i64,
+    task_id: u32,
+) -> Result<(), String> {
+    let bot_token = get_bot_token(state)?;
+    let task = state
+        .db
+        .collection::<QuestTaskDocument>("tasks")
+        .find_one(
+            doc! { "quest_id": quest_id, "id": task_id, "task_type": "telegram" },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error querying task: {}", e))?
+        .ok_or_else(|| "Error querying task".to_string())?;
+    let chat_id = task
+        .telegram_chat_id
+        .ok_or_else(|| "Task has no Telegram chat".to_string())?;
+    let Some(account) = get_linked_account(state, addr, "telegram").await? else {
+        return Err("Please link your Telegram account first".to_string());
+    };
+
+    if !is_chat_member(bot_token, &chat_id, &account.account_id).await? {
+        return Err("You're not part of the Telegram group".to_string());
+    }
+    record_account_task(state, &account, task_id).await?;
+    state
+        .upsert_completed_task(addr, task_id)
+        .await
+        .map_err(|e| format!("{}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOT_TOKEN: &str = "123456:bot-token";
+    const AUTH_DATE: i64 = 1_700_000_000;
+
+    // signs the data like the login widget does
+    fn login_data(username: &str) -> TelegramAuthData {
+        let check_string = format!(
+            "auth_date={}\nfirst_name=Alice\nid=42\nusername={}",
+            AUTH_DATE, username
+        );
+        let secret_key = Sha256::digest(BOT_TOKEN.as_bytes());
+        let mut mac = Hmac::<Sha256>::new_from_slice(&secret_key).unwrap();
+        mac.update(check_string.as_bytes());
+        TelegramAuthData {
+            id: 42,
+            first_name: Some("Alice".to_string()),
+            last_name: None,
+            username: Some(username.to_string()),
+            photo_url: None,
+            auth_date: AUTH_DATE,
+            hash: hex::encode(mac.finalize().into_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_verify_valid_login() {
+        assert_eq!(
+            verify_login(BOT_TOKEN, &login_data("alice"), AUTH_DATE + 60),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_verify_tampered_login() {
+        let mut data = login_data("alice");
+        data.username = Some("bob".to_string());
+        assert_eq!(
+            verify_login(BOT_TOKEN, &data, AUTH_DATE + 60),
+            Err("Invalid Telegram login".to_string())
+        );
+        let mut data = login_data("alice");
+        data.id = 43;
+        assert!(verify_login(BOT_TOKEN, &data, AUTH_DATE + 60).is_err());
+        assert!(verify_login("654321:other-token", &login_data("alice"), AUTH_DATE + 60).is_err());
+    }
+
+    #[test]
+    fn test_verify_stale_login() {
+        let data = login_data("alice");
+        assert!(verify_login(BOT_TOKEN, &data, AUTH_DATE + AUTH_MAX_AGE_SECS).is_ok());
+        assert_eq!(
+            verify_login(BOT_TOKEN, &data, AUTH_DATE + AUTH_MAX_AGE_SECS + 1),
+            Err("Your Telegram login expired, please try again".to_string())
+        );
+    }
+}
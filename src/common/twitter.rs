@@ -0,0 +1,303 @@
+use lazy_static::lazy_static;
+use mongodb::bson::doc;
+use regex::Regex;
+use serde_json::Value;
+use starknet::core::types::FieldElement;
+
+use crate::{
+    common::linked_accounts::{get_access_token, record_account_task},
+    models::{AppState, LinkedAccountDocument, QuestTaskDocument, TwitterAction},
+    utils::CompletedTasksTrait,
+};
+
+const API_URL: &str = "https://api.twitter.com/2";
+// pages of the user's followings, likes or posts looked through before giving up. Lists are
+// read from the user's side as X only returns the first hundred likers or retweeters of a post
+const MAX_PAGES: usize = 10;
+
+lazy_static! {
+    static ref TWEET_ID: Regex = Regex::new(r"/status(?:es)?/(\d+)").unwrap();
+}
+
+pub fn get_tweet_id(post_link: &str) -> Option<String> {
+    TWEET_ID
+        .captures(post_link)
+        .map(|captures| captures[1].to_string())
+}
+
+pub fn get_tweet_action(action: &str, post_link: &str) -> Result<TwitterAction, String> {
+    let tweet_id = get_tweet_id(post_link).ok_or_else(|| "Invalid post link".to_string())?;
+    match action {
+        "retweet" => Ok(TwitterAction::Retweet { tweet_id }),
+        "like" => Ok(TwitterAction::Like { tweet_id }),
+        "quote" => Ok(TwitterAction::Quote { tweet_id }),
+        _ => Err(format!(
+            "Unsupported action {}, expected retweet, like or quote",
+            action
+        )),
+    }
+}
+
+pub fn get_tweet_cta(action: &TwitterAction) -> String {
+    match action {
+        TwitterAction::Follow { .. } => "Follow",
+        TwitterAction::Retweet { .. } => "Retweet",
+        TwitterAction::Like { .. } => "Like",
+        TwitterAction::Quote { .. } => "Quote",
+    }
+    .to_string()
+}
+
+// tasks created before actions were stored only have their target in their href
+pub fn get_task_action(task: &QuestTaskDocument) -> Option<TwitterAction> {
+    if let Some(action) = &task.twitter_action {
+        return Some(action.clone());
+    }
+    match task.task_type.as_deref() {
+        Some("twitter_fw") => {
+            let username = task.href.trim_end_matches('/').rsplit('/').next()?;
+            Some(TwitterAction::Follow {
+                username: username.to_string(),
+            })
+        }
+        Some("twitter_rw") => Some(TwitterAction::Retweet {
+            tweet_id: get_tweet_id(&task.href)?,
+        }),
+        _ => None,
+    }
+}
+
+async fn get_api(access_token: &str, path: &str, params: &[(&str, &str)]) -> Result<Value, String> {
+    let response = reqwest::Client::new()
+        .get(format!("{}{}", API_URL, path))
+        .bearer_auth(access_token)
+        .query(params)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request to X: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("X returned status {}", response.status()));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to get JSON response from X: {}", e))
+}
+
+// whether a post of the user's timeline is a retweet or a quote of the given post
+fn is_reference_to(item: &Value, reference_type: &str, tweet_id: &str) -> bool {
+    item["referenced_tweets"]
+        .as_array()
+        .map(|references| {
+            references.iter().any(|reference| {
+                reference["type"].as_str() == Some(reference_type)
+                    && reference["id"].as_str() == Some(tweet_id)
+            })
+        })
+        .unwrap_or(false)
+}
+
+// looks through the pages of a list endpoint for an item matching the predicate
+async fn find_in_pages(
+    access_token: &str,
+    path: &str,
+    params: &[(&str, &str)],
+    matches: impl Fn(&Value) -> bool,
+) -> Result<bool, String> {
+    let mut pagination_token: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let mut page_params = params.to_vec();
+        if let Some(token) = &pagination_token {
+            page_params.push(("pagination_token", token.as_str()));
+        }
+        let page = get_api(access_token, path, &page_params).await?;
+        if let Some(items) = page["data"].as_array() {
+            if items.iter().any(&matches) {
+                return Ok(true);
+            }
+        }
+        match page["meta"]["next_token"].as_str() {
+            Some(token) => pagination_token = Some(token.to_string()),
+            None => break,
+        }
+    }
+    Ok(false)
+}
+
+// checks the action was made by the X account linked to the address, which is returned
+pub async fn verify_action(
+    state: &AppState,
+    addr: FieldElement,
+    action: &TwitterAction,
+) -> Result<LinkedAccountDocument, String> {
+    let (account, access_token) = get_access_token(state, addr, "twitter").await?;
+    let user_id = account.account_id.as_str();
+
+    let (done, error) = match action {
+        TwitterAction::Follow { username } => {
+            let path = format!("/users/{}/following", user_id);
+            let is_target = |item: &Value| {
+                item["username"]
+                    .as_str()
+                    .map(|name| name.eq_ignore_ascii_case(username))
+                    .unwrap_or(false)
+            };
+            let params = [("max_results", "1000")];
+            let done = find_in_pages(&access_token, &path, &params, is_target).await?;
+            (done, format!("You're not following @{}", username))
+        }
+        TwitterAction::Retweet { tweet_id } => {
+            let path = format!("/users/{}/tweets", user_id);
+            let params = [
+                ("max_results", "100"),
+                ("tweet.fields", "referenced_tweets"),
+            ];
+            let is_retweet = |item: &Value| is_reference_to(item, "retweeted", tweet_id);
+            let done = find_in_pages(&access_token, &path, &params, is_retweet).await?;
+            (done, "You haven't retweeted this post".to_string())
+        }
+        TwitterAction::Like { tweet_id } => {
+            let path = format!("/users/{}/liked_tweets", user_id);
+            let params = [("max_results", "100")];
+            let is_tweet = |item: &Value| item["id"].as_str() == Some(tweet_id.as_str());
+            let done = find_in_pages(&access_token, &path, &params, is_tweet).await?;
+            (done, "You haven't liked this post".to_string())
+        }
+        TwitterAction::Quote { tweet_id } => {
+            let path = format!("/users/{}/tweets", user_id);
+            let params = [
+                ("max_results", "100"),
+                ("tweet.fields", "referenced_tweets"),
+            ];
+            let is_quote = |item: &Value| is_reference_to(item, "quoted", tweet_id);
+            let done = find_in_pages(&access_token, &path, &params, is_quote).await?;
+            (done, "You haven't quoted this post".to_string())
+        }
+    };
+    if done {
+        Ok(account)
+    } else {
+        Err(error)
+    }
+}
+
+// verifies a twitter task of the quest for the address and records it as completed
+pub async fn verify_task(
+    state: &AppState,
+    addr: FieldElement,
+    quest_id: i64,
+    task_id: u32,
+) -> Result<(), String> {
+    let task = state
+        .db
+        .collection::<QuestTaskDocument>("tasks")
+        .find_one(
+            doc! {
+                "quest_id": quest_id,
+                "id": task_id,
+                "task_type": { "$in": ["twitter_fw", "twitter_rw"] },
+            },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error querying task: {}", e))?
+        .ok_or_else(|| "Error querying task".to_string())?;
+    let action = get_task_action(&task).ok_or_else(|| "Task has no X target".to_string())?;
+    let account = verify_action(state, addr, &action).await?;
+    record_account_task(state, &account, task_id).await?;
+    state
+        .upsert_completed_task(addr, task_id)
+        .await
+        .map_err(|e| format!("{}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn task(
+        task_type: &str,
+        href: &str,
+        twitter_action: Option<TwitterAction>,
+    ) -> QuestTaskDocument {
+        serde_json::from_value(json!({
+            "id": 1,
+            "quest_id": 1,
+            "name": "X task",
+            "desc": "",
+            "cta": "",
+            "verify_endpoint": "",
+            "href": href,
+            "verify_endpoint_type": "default",
+            "task_type": task_type,
+            "twitter_action": twitter_action,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_get_tweet_id() {
+        assert_eq!(
+            get_tweet_id("https://x.com/starknetquest/status/1769344829234798871"),
+            Some("1769344829234798871".to_string())
+        );
+        assert_eq!(
+            get_tweet_id("https://twitter.com/i/web/statuses/1769344829234798871?s=20"),
+            Some("1769344829234798871".to_string())
+        );
+        assert_eq!(get_tweet_id("https://x.com/starknetquest"), None);
+        assert_eq!(get_tweet_id("https://x.com/starknetquest/status/abc"), None);
+    }
+
+    #[test]
+    fn test_get_task_action() {
+        // the stored action takes precedence over the href
+        let like = TwitterAction::Like {
+            tweet_id: "42".to_string(),
+        };
+        let stored = task("twitter_rw", "https://x.com/a/status/1", Some(like.clone()));
+        assert_eq!(get_task_action(&stored), Some(like));
+
+        let follow = task("twitter_fw", "https://x.com/starknetquest/", None);
+        assert_eq!(
+            get_task_action(&follow),
+            Some(TwitterAction::Follow {
+                username: "starknetquest".to_string()
+            })
+        );
+        let retweet = task(
+            "twitter_rw",
+            "https://x.com/a/status/1769344829234798871",
+            None,
+        );
+        assert_eq!(
+            get_task_action(&retweet),
+            Some(TwitterAction::Retweet {
+                tweet_id: "1769344829234798871".to_string()
+            })
+        );
+
+        assert_eq!(
+            get_task_action(&task("twitter_rw", "https://x.com/a", None)),
+            None
+        );
+        assert_eq!(
+            get_task_action(&task("discord", "https://x.com/a/status/1", None)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_reference_to() {
+        let post = json!({
+            "id": "2",
+            "referenced_tweets": [{ "type": "quoted", "id": "1" }],
+        });
+        assert!(is_reference_to(&post, "quoted", "1"));
+        assert!(!is_reference_to(&post, "retweeted", "1"));
+        assert!(!is_reference_to(&post, "quoted", "3"));
+        assert!(!is_reference_to(&json!({ "id": "1" }), "retweeted", "1"));
+    }
+}
@@ -0,0 +1,437 @@
+use std::str::FromStr;
+
+use futures::future::{BoxFuture, FutureExt};
+use num_bigint::BigUint;
+use regex::Regex;
+use serde_json::Value;
+use starknet::core::{types::FieldElement, utils::get_selector_from_name};
+
+use crate::{
+    models::{AppState, Call, ComparisonOperator, ResultPredicate, ResultType, VerificationRule},
+    utils::{parse_decimal_amount, parse_string, read_contract},
+};
+
+// nested rules are limited so a malformed task cannot recurse forever
+const MAX_RULE_DEPTH: usize = 8;
+
+// fields of json responses are selected with JSON pointers, e.g. /data/0/volume, an empty pointer
+// selecting the whole response
+pub fn validate_json_pointer(pointer: &str) -> Result<(), String> {
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return Err(format!("JSON pointer must start with '/': {}", pointer));
+    }
+    Ok(())
+}
+
+fn compare<T: PartialOrd>(left: T, operator: &ComparisonOperator, right: T) -> bool {
+    match operator {
+        ComparisonOperator::Eq => left == right,
+        ComparisonOperator::Neq => left != right,
+        ComparisonOperator::Gt => left > right,
+        ComparisonOperator::Gte => left >= right,
+        ComparisonOperator::Lt => left < right,
+        ComparisonOperator::Lte => left <= right,
+    }
+}
+
+fn compare_json(left: &Value, operator: &ComparisonOperator, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => match (l.as_f64(), r.as_f64()) {
+            (Some(l), Some(r)) => compare(l, operator, r),
+            _ => false,
+        },
+        (Value::String(l), Value::String(r)) => compare(l, operator, r),
+        // APIs often return numbers as strings, e.g. token amounts
+        (Value::String(l), Value::Number(r)) => match (l.parse::<f64>(), r.as_f64()) {
+            (Ok(l), Some(r)) => compare(l, operator, r),
+            _ => false,
+        },
+        _ => match operator {
+            ComparisonOperator::Eq => left == right,
+            ComparisonOperator::Neq => left != right,
+            _ => false,
+        },
+    }
+}
+
+fn parse_selector(entry_point: &str) -> Result<FieldElement, String> {
+    match entry_point.starts_with("0x") {
+        true => FieldElement::from_hex_be(entry_point)
+            .map_err(|e| format!("Invalid entry point {}: {}", entry_point, e)),
+        false => get_selector_from_name(entry_point)
+            .map_err(|e| format!("Invalid entry point {}: {}", entry_point, e)),
+    }
+}
+
+fn parse_felt(value: &str, addr: FieldElement) -> Result<FieldElement, String> {
+    FieldElement::from_str(&parse_string(value, addr))
+        .map_err(|e| format!("Invalid felt {}: {}", value, e))
+}
+
+fn felt_to_biguint(felt: &FieldElement) -> BigUint {
+    BigUint::from_bytes_be(&felt.to_bytes_be())
+}
+
+fn parse_predicate_value(predicate: &ResultPredicate) -> Result<BigUint, String> {
+    match (&predicate.result_type, predicate.value.as_str()) {
+        (ResultType::Bool, "true") => Ok(BigUint::from(1u8)),
+        (ResultType::Bool, "false") => Ok(BigUint::from(0u8)),
+        (ResultType::Bool, value) => Err(format!("Invalid bool value: {}", value)),
+        (_, value) if value.starts_with("0x") => FieldElement::from_hex_be(value)
+            .map(|felt| felt_to_biguint(&felt))
+            .map_err(|e| format!("Invalid value {}: {}", value, e)),
+        (_, value) => parse_decimal_amount(value, predicate.decimals.unwrap_or(0)),
+    }
+}
+
+pub fn validate_predicate(predicate: &ResultPredicate) -> Result<(), String> {
+    if predicate.result_type == ResultType::Bool
+        && !matches!(
+            predicate.operator,
+            ComparisonOperator::Eq | ComparisonOperator::Neq
+        )
+    {
+        return Err("Bool results can only be compared with eq or neq".to_string());
+    }
+    parse_predicate_value(predicate).map(|_| ())
+}
+
+// evaluates a predicate natively against the raw felts returned by a contract call
+pub fn evaluate_predicate(
+    result: &[FieldElement],
+    predicate: &ResultPredicate,
+) -> Result<bool, String> {
+    let index = predicate.index as usize;
+    let get_felt = |index: usize| {
+        result.get(index).ok_or(format!(
+            "Contract call returned {} values, index {} is out of bounds",
+            result.len(),
+            index
+        ))
+    };
+
+    let actual = match predicate.result_type {
+        ResultType::Felt => felt_to_biguint(get_felt(index)?),
+        // u256 values are returned as two felts: low then high
+        ResultType::U256 => {
+            let low = felt_to_biguint(get_felt(index)?);
+            let high = felt_to_biguint(get_felt(index + 1)?);
+            (high << 128) + low
+        }
+        ResultType::Bool => match *get_felt(index)? == FieldElement::ZERO {
+            true => BigUint::from(0u8),
+            false => BigUint::from(1u8),
+        },
+    };
+
+    Ok(compare(
+        actual,
+        &predicate.operator,
+        parse_predicate_value(predicate)?,
+    ))
+}
+
+pub fn validate_call(call: &Call) -> Result<(), String> {
+    FieldElement::from_hex_be(&call.contract)
+        .map_err(|e| format!("Invalid contract address {}: {}", call.contract, e))?;
+    FieldElement::from_hex_be(&call.entry_point)
+        .map_err(|e| format!("Invalid entry point {}: {}", call.entry_point, e))?;
+    for calldata in &call.call_data {
+        FieldElement::from_hex_be(&parse_string(calldata, FieldElement::ZERO))
+            .map_err(|e| format!("Invalid calldata {}: {}", calldata, e))?;
+    }
+    match (&call.predicates, &call.regex) {
+        (Some(predicates), _) if !predicates.is_empty() => {
+            predicates.iter().try_for_each(validate_predicate)
+        }
+        (_, Some(regex)) => Regex::new(&parse_string(regex, FieldElement::ZERO))
+            .map(|_| ())
+            .map_err(|e| format!("Invalid regex {}: {}", regex, e)),
+        _ => Err("Each call needs predicates or a regex".to_string()),
+    }
+}
+
+// a contract call rule is a predicate on the call result, its value being able to refer to the
+// address like the calldata
+fn get_rule_predicate(
+    rule: &VerificationRule,
+    addr: FieldElement,
+) -> Result<ResultPredicate, String> {
+    let VerificationRule::ContractCall {
+        result_index,
+        result_type,
+        operator,
+        value,
+        decimals,
+        ..
+    } = rule
+    else {
+        return Err("Only contract call rules have a predicate".to_string());
+    };
+    Ok(ResultPredicate {
+        index: *result_index,
+        result_type: result_type.clone().unwrap_or(ResultType::Felt),
+        operator: operator.clone(),
+        value: parse_string(value, addr),
+        decimals: *decimals,
+    })
+}
+
+pub fn validate_rule(rule: &VerificationRule) -> Result<(), String> {
+    validate_rule_with_depth(rule, 0)
+}
+
+fn validate_rule_with_depth(rule: &VerificationRule, depth: usize) -> Result<(), String> {
+    if depth > MAX_RULE_DEPTH {
+        return Err(format!(
+            "Rules cannot be nested more than {} times",
+            MAX_RULE_DEPTH
+        ));
+    }
+
+    match rule {
+        VerificationRule::ContractCall {
+            contract,
+            entry_point,
+            call_data,
+            ..
+        } => {
+            FieldElement::from_hex_be(contract)
+                .map_err(|e| format!("Invalid contract address {}: {}", contract, e))?;
+            parse_selector(entry_point)?;
+            for calldata in call_data {
+                parse_felt(calldata, FieldElement::ZERO)?;
+            }
+            validate_predicate(&get_rule_predicate(rule, FieldElement::ZERO)?)
+        }
+        VerificationRule::HttpProbe { url, field, .. } => {
+            if !url.starts_with("https://") && !url.starts_with("http://") {
+                return Err(format!("Invalid url: {}", url));
+            }
+            validate_json_pointer(field)
+        }
+        VerificationRule::And { rules } | VerificationRule::Or { rules } => {
+            if rules.is_empty() {
+                return Err("Boolean rules must contain at least one sub-rule".to_string());
+            }
+            rules
+                .iter()
+                .try_for_each(|rule| validate_rule_with_depth(rule, depth + 1))
+        }
+    }
+}
+
+pub fn evaluate_rule<'a>(
+    state: &'a AppState,
+    rule: &'a VerificationRule,
+    addr: FieldElement,
+) -> BoxFuture<'a, Result<bool, String>> {
+    async move {
+        match rule {
+            VerificationRule::ContractCall {
+                contract,
+                entry_point,
+                call_data,
+                ..
+            } => {
+                let contract = FieldElement::from_hex_be(contract)
+                    .map_err(|e| format!("Invalid contract address: {}", e))?;
+                let selector = parse_selector(entry_point)?;
+                let calldata = call_data
+                    .iter()
+                    .map(|calldata| parse_felt(calldata, addr))
+                    .collect::<Result<Vec<FieldElement>, String>>()?;
+                let predicate = get_rule_predicate(rule, addr)?;
+
+                let result = read_contract(state, contract, selector, calldata)
+                    .await
+                    .map_err(|e| format!("Contract call failed: {}", e))?;
+                evaluate_predicate(&result, &predicate)
+            }
+            VerificationRule::HttpProbe {
+                url,
+                field,
+                operator,
+                value,
+            } => {
+                let response = reqwest::get(parse_string(url, addr))
+                    .await
+                    .map_err(|e| format!("Failed to fetch API: {}", e))?;
+                let json = response
+                    .json::<Value>()
+                    .await
+                    .map_err(|e| format!("Failed to get JSON response: {}", e))?;
+                Ok(json
+                    .pointer(field)
+                    .map(|actual| compare_json(actual, operator, value))
+                    .unwrap_or(false))
+            }
+            VerificationRule::And { rules } => {
+                for rule in rules {
+                    if !evaluate_rule(state, rule, addr).await? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            VerificationRule::Or { rules } => {
+                for rule in rules {
+                    if evaluate_rule(state, rule, addr).await? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+        }
+    }
+    .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_select_and_compare_json() {
+        let response = json!({ "data": [{ "volume": "1500.5" }, { "eligible": true }] });
+        assert!(validate_json_pointer("/data/0/volume").is_ok());
+        assert!(validate_json_pointer("$.data[0].volume").is_err());
+
+        let volume = response.pointer("/data/0/volume");
+        assert!(compare_json(
+            volume.unwrap(),
+            &ComparisonOperator::Gte,
+            &json!(1000)
+        ));
+        assert!(!compare_json(
+            volume.unwrap(),
+            &ComparisonOperator::Lt,
+            &json!(1000)
+        ));
+
+        let eligible = response.pointer("/data/1/eligible");
+        assert!(compare_json(
+            eligible.unwrap(),
+            &ComparisonOperator::Eq,
+            &json!(true)
+        ));
+        assert!(response.pointer("/data/2").is_none());
+    }
+
+    #[test]
+    fn test_evaluate_predicate() {
+        let predicate = |result_type, operator, value: &str, decimals| ResultPredicate {
+            index: 1,
+            result_type,
+            operator,
+            value: value.to_string(),
+            decimals,
+        };
+        let ten_strk = FieldElement::from_dec_str("10000000000000000000").unwrap();
+
+        // balance >= 10 STRK
+        let result = [FieldElement::ONE, ten_strk, FieldElement::ZERO];
+        let gte = predicate(ResultType::U256, ComparisonOperator::Gte, "10", Some(18));
+        assert_eq!(evaluate_predicate(&result, &gte), Ok(true));
+        let gt = predicate(ResultType::U256, ComparisonOperator::Gt, "10.5", Some(18));
+        assert_eq!(evaluate_predicate(&result, &gt), Ok(false));
+
+        // a non zero high part is larger than any u128 threshold
+        let result = [FieldElement::ZERO, FieldElement::ZERO, FieldElement::ONE];
+        let huge = predicate(
+            ResultType::U256,
+            ComparisonOperator::Gt,
+            "340282366920938463463374607431768211455",
+            None,
+        );
+        assert_eq!(evaluate_predicate(&result, &huge), Ok(true));
+
+        let result = [FieldElement::ZERO, FieldElement::ONE];
+        let is_true = predicate(ResultType::Bool, ComparisonOperator::Eq, "true", None);
+        assert_eq!(evaluate_predicate(&result, &is_true), Ok(true));
+        let felt = predicate(ResultType::Felt, ComparisonOperator::Lt, "0x2", None);
+        assert_eq!(evaluate_predicate(&result, &felt), Ok(true));
+
+        let out_of_bounds = predicate(ResultType::U256, ComparisonOperator::Eq, "1", None);
+        assert!(evaluate_predicate(&result, &out_of_bounds).is_err());
+        let too_precise = predicate(ResultType::Felt, ComparisonOperator::Eq, "1.25", Some(1));
+        assert!(validate_predicate(&too_precise).is_err());
+        let bool_gt = predicate(ResultType::Bool, ComparisonOperator::Gt, "true", None);
+        assert!(validate_predicate(&bool_gt).is_err());
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        let rule: VerificationRule = serde_json::from_value(json!({
+            "type": "or",
+            "rules": [
+                {
+                    "type": "contract_call",
+                    "contract": "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d",
+                    "entry_point": "balanceOf",
+                    "call_data": ["{addr_hex}"],
+                    "result_index": 0,
+                    "operator": "gte",
+                    "value": "1000000000000000000"
+                },
+                {
+                    "type": "http_probe",
+                    "url": "https://api.example.com/{addr_hex}",
+                    "field": "/result",
+                    "operator": "eq",
+                    "value": true
+                }
+            ]
+        }))
+        .unwrap();
+        assert!(validate_rule(&rule).is_ok());
+        assert!(validate_rule(&VerificationRule::And { rules: vec![] }).is_err());
+    }
+
+    #[test]
+    fn test_contract_call_rule_predicate() {
+        let rule: VerificationRule = serde_json::from_value(json!({
+            "type": "contract_call",
+            "contract": "0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d",
+            "entry_point": "balanceOf",
+            "call_data": ["{addr_hex}"],
+            "result_index": 0,
+            "result_type": "u256",
+            "operator": "gte",
+            "value": "10",
+            "decimals": 18
+        }))
+        .unwrap();
+        assert!(validate_rule(&rule).is_ok());
+        let predicate = get_rule_predicate(&rule, FieldElement::ONE).unwrap();
+        let ten_strk = FieldElement::from_dec_str("10000000000000000000").unwrap();
+        assert_eq!(
+            evaluate_predicate(&[ten_strk, FieldElement::ZERO], &predicate),
+            Ok(true)
+        );
+        assert_eq!(
+            evaluate_predicate(&[FieldElement::ONE, FieldElement::ZERO], &predicate),
+            Ok(false)
+        );
+
+        // rules without a result type compare a single felt, possibly with the address
+        let rule: VerificationRule = serde_json::from_value(json!({
+            "type": "contract_call",
+            "contract": "0x1",
+            "entry_point": "owner_of",
+            "call_data": ["1"],
+            "result_index": 0,
+            "operator": "eq",
+            "value": "{addr_hex}"
+        }))
+        .unwrap();
+        let addr = FieldElement::from_hex_be("0x123").unwrap();
+        let predicate = get_rule_predicate(&rule, addr).unwrap();
+        assert_eq!(evaluate_predicate(&[addr], &predicate), Ok(true));
+        assert_eq!(
+            evaluate_predicate(&[FieldElement::ONE], &predicate),
+            Ok(false)
+        );
+    }
+}
@@ -15,13 +15,13 @@ use starknet::{
     providers::Provider,
 };
 
-pub async fn execute_has_root_domain(
-    state: Arc<AppState>,
+// returns the expiry of the root domain the address resolves to, None if it is not a root domain
+pub async fn get_root_domain_expiry(
+    state: &AppState,
     addr: &FieldElement,
-    task_id: u32,
-) -> impl IntoResponse {
+) -> Result<Option<u64>, String> {
     // get starkname from address
-    let call_result = state
+    let result = state
         .provider
         .call(
             FunctionCall {
@@ -31,47 +31,55 @@ pub async fn execute_has_root_domain(
             },
             BlockId::Tag(BlockTag::Latest),
         )
-        .await;
+        .await
+        .map_err(|e| format!("{}", e))?;
+
+    let domain_len = i64::from_str_radix(&FieldElement::to_string(&result[0]), 16).unwrap();
+    if domain_len != 1 {
+        return Ok(None);
+    }
 
-    match call_result {
-        Ok(result) => {
-            let domain_len = i64::from_str_radix(&FieldElement::to_string(&result[0]), 16).unwrap();
+    // get expiry
+    let Ok(expiry_result) = state
+        .provider
+        .call(
+            FunctionCall {
+                contract_address: state.conf.starknetid_contracts.naming_contract,
+                entry_point_selector: selector!("domain_to_expiry"),
+                calldata: vec![FieldElement::ONE, result[1]],
+            },
+            BlockId::Tag(BlockTag::Latest),
+        )
+        .await
+    else {
+        return Err("error querying expiry".to_string());
+    };
+    let Ok(expiry): Result<u64, _> = expiry_result[0].try_into() else {
+        return Err("error reading expiry".to_string());
+    };
+    Ok(Some(expiry))
+}
 
-            if domain_len == 1 {
-                // get expiry
-                let Ok(expiry_result) = state
-                    .provider
-                    .call(
-                        FunctionCall {
-                            contract_address: state.conf.starknetid_contracts.naming_contract,
-                            entry_point_selector: selector!("domain_to_expiry"),
-                            calldata: vec![FieldElement::ONE, result[1]],
-                        },
-                        BlockId::Tag(BlockTag::Latest),
-                    )
-                    .await
-                else {
-                    return get_error("error querying expiry".to_string());
-                };
-                let Ok(expiry): Result<u64, _> = expiry_result[0].try_into() else {
-                    return get_error("error reading expiry".to_string());
-                };
-                let now = match SystemTime::now().duration_since(UNIX_EPOCH) {
-                    Ok(n) => n.as_secs(),
-                    Err(_) => return get_error("system time before UNIX EPOCH".to_string()),
-                };
-                if expiry < now {
-                    return get_error("expired domain".to_string());
-                }
+pub async fn execute_has_root_domain(
+    state: Arc<AppState>,
+    addr: &FieldElement,
+    task_id: u32,
+) -> impl IntoResponse {
+    let expiry = match get_root_domain_expiry(&state, addr).await {
+        Ok(Some(expiry)) => expiry,
+        Ok(None) => return get_error("Invalid domain: subdomains are not eligible".to_string()),
+        Err(e) => return get_error(e),
+    };
+    let now = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(n) => n.as_secs(),
+        Err(_) => return get_error("system time before UNIX EPOCH".to_string()),
+    };
+    if expiry < now {
+        return get_error("expired domain".to_string());
+    }
 
-                match state.upsert_completed_task(*addr, task_id).await {
-                    Ok(_) => (StatusCode::OK, Json(json!({"res": true}))).into_response(),
-                    Err(e) => get_error(format!("{}", e)),
-                }
-            } else {
-                get_error("Invalid domain: subdomains are not eligible".to_string())
-            }
-        }
+    match state.upsert_completed_task(*addr, task_id).await {
+        Ok(_) => (StatusCode::OK, Json(json!({"res": true}))).into_response(),
         Err(e) => get_error(format!("{}", e)),
     }
 }
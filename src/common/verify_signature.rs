@@ -0,0 +1,104 @@
+use serde_json::{json, Value};
+use starknet::{
+    core::{
+        crypto::compute_hash_on_elements,
+        types::FieldElement,
+        utils::{cairo_short_string_to_felt, starknet_keccak},
+    },
+    macros::selector,
+};
+
+use crate::{
+    models::AppState,
+    utils::{read_contract, to_hex},
+};
+
+const DOMAIN_NAME: &str = "StarknetQuest";
+const DOMAIN_TYPE: &str = "StarkNetDomain(name:felt,chainId:felt,version:felt)";
+const CHALLENGE_TYPE: &str = "Challenge(nonce:felt,expiry:felt)";
+
+fn get_chain_id(is_testnet: bool) -> &'static str {
+    match is_testnet {
+        true => "SN_SEPOLIA",
+        false => "SN_MAIN",
+    }
+}
+
+// SNIP-12 (revision 0) typed data the wallet is asked to sign, in the format
+// expected by `account.signMessage` in starknet.js
+pub fn get_challenge_typed_data(is_testnet: bool, nonce: FieldElement, expiry: i64) -> Value {
+    json!({
+        "types": {
+            "StarkNetDomain": [
+                { "name": "name", "type": "felt" },
+                { "name": "chainId", "type": "felt" },
+                { "name": "version", "type": "felt" }
+            ],
+            "Challenge": [
+                { "name": "nonce", "type": "felt" },
+                { "name": "expiry", "type": "felt" }
+            ]
+        },
+        "primaryType": "Challenge",
+        "domain": {
+            "name": DOMAIN_NAME,
+            "chainId": get_chain_id(is_testnet),
+            "version": "1"
+        },
+        "message": {
+            "nonce": to_hex(nonce),
+            "expiry": expiry.to_string()
+        }
+    })
+}
+
+pub fn get_challenge_message_hash(
+    is_testnet: bool,
+    addr: FieldElement,
+    nonce: FieldElement,
+    expiry: i64,
+) -> Result<FieldElement, String> {
+    let short_string = |s: &str| {
+        cairo_short_string_to_felt(s).map_err(|e| format!("Invalid short string {}: {}", s, e))
+    };
+
+    let domain_hash = compute_hash_on_elements(&[
+        starknet_keccak(DOMAIN_TYPE.as_bytes()),
+        short_string(DOMAIN_NAME)?,
+        short_string(get_chain_id(is_testnet))?,
+        FieldElement::ONE,
+    ]);
+    let message_hash = compute_hash_on_elements(&[
+        starknet_keccak(CHALLENGE_TYPE.as_bytes()),
+        nonce,
+        FieldElement::from(expiry as u64),
+    ]);
+
+    Ok(compute_hash_on_elements(&[
+        short_string("StarkNet Message")?,
+        domain_hash,
+        addr,
+        message_hash,
+    ]))
+}
+
+pub async fn execute_is_valid_signature(
+    state: &AppState,
+    addr: FieldElement,
+    hash: FieldElement,
+    signature: &[FieldElement],
+) -> Result<bool, String> {
+    let mut calldata = vec![hash, FieldElement::from(signature.len())];
+    calldata.extend_from_slice(signature);
+
+    match read_contract(state, addr, selector!("is_valid_signature"), calldata).await {
+        // Cairo 1 accounts return the 'VALID' short string, legacy accounts return 1
+        Ok(result) => Ok(matches!(
+            result.first(),
+            Some(res) if *res == cairo_short_string_to_felt("VALID").unwrap()
+                || *res == FieldElement::ONE
+        )),
+        // most accounts revert on an invalid signature instead of returning 0
+        Err(e) => Err(format!("Unable to verify signature: {}", e)),
+    }
+}
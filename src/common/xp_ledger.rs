@@ -0,0 +1,282 @@
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    bson::{doc, oid::ObjectId, to_bson, Bson, Document},
+    error::{Error, ErrorKind, WriteFailure},
+    options::IndexOptions,
+    Database, IndexModel,
+};
+
+use crate::{common::leaderboard::get_day_bucket, models::XpSource, utils::update_leaderboard};
+
+const DUPLICATE_KEY_CODE: i32 = 11000;
+
+pub fn is_duplicate_key(error: &Error) -> bool {
+    matches!(
+        *error.kind,
+        ErrorKind::Write(WriteFailure::WriteError(ref write_error))
+            if write_error.code == DUPLICATE_KEY_CODE
+    )
+}
+
+// a quest or an achievement is credited at most once per address and an entry reversed at most
+// once, entries predating sources being ignored
+pub async fn add_ledger_indexes(db: &Database) {
+    let ledger = db.collection::<Document>("user_exp");
+    let unique_options = IndexOptions::builder()
+        .unique(true)
+        .partial_filter_expression(doc! { "source.type": "achievement" })
+        .build();
+    let achievement_index = IndexModel::builder()
+        .keys(doc! { "address": 1, "source.achievement_id": 1 })
+        .options(unique_options)
+        .build();
+    ledger.create_index(achievement_index, None).await.unwrap();
+
+    let unique_options = IndexOptions::builder()
+        .unique(true)
+        .partial_filter_expression(doc! { "source.type": "quest" })
+        .build();
+    let quest_index = IndexModel::builder()
+        .keys(doc! { "address": 1, "source.quest_id": 1 })
+        .options(unique_options)
+        .build();
+    ledger.create_index(quest_index, None).await.unwrap();
+
+    let unique_options = IndexOptions::builder()
+        .unique(true)
+        .partial_filter_expression(doc! { "source.type": "reversal" })
+        .build();
+    let reversal_index = IndexModel::builder()
+        .keys(doc! { "source.entry_id": 1 })
+        .options(unique_options)
+        .build();
+    ledger.create_index(reversal_index, None).await.unwrap();
+}
+
+// ledger entries were written with int32, int64 or double values over time
+fn get_number(entry: &Document, field: &str) -> Option<f64> {
+    match entry.get(field) {
+        Some(Bson::Int32(value)) => Some(*value as f64),
+        Some(Bson::Int64(value)) => Some(*value as f64),
+        Some(Bson::Double(value)) => Some(*value),
+        _ => None,
+    }
+}
+
+// records the experience in the user_exp ledger along with its source and credits the leaderboard
+pub async fn credit_experience(
+    db: &Database,
+    address: String,
+    experience: i64,
+    source: XpSource,
+) -> Result<(), Error> {
+    let timestamp: f64 = Utc::now().timestamp_millis() as f64;
+    let entry = doc! {
+        "address": &address,
+        "experience": experience,
+        "timestamp": timestamp,
+        "source": to_bson(&source)?,
+    };
+    db.collection::<Document>("user_exp")
+        .insert_one(entry, None)
+        .await?;
+    update_leaderboard(db, address, experience, timestamp).await;
+    Ok(())
+}
+
+// credits the experience of an achievement unless it was already credited to the address,
+// returning whether it was
+pub async fn credit_achievement(
+    db: &Database,
+    address: String,
+    achievement_id: u32,
+    experience: i64,
+) -> Result<bool, Error> {
+    if experience <= 0 {
+        return Ok(false);
+    }
+    let source = XpSource::Achievement { achievement_id };
+    match credit_experience(db, address, experience, source).await {
+        Ok(_) => Ok(true),
+        Err(e) if is_duplicate_key(&e) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+// cancels a ledger entry with an opposite one dated like the original, so that rebuilding the
+// leaderboard from the ledger removes the experience from the same day. Returns the experience
+// reversed, None if the entry does not exist or was already reversed.
+// The entry is only flagged once its reversal is recorded so that a failed reversal can be
+// retried, the reversal being unique per entry so that a retry never records it twice
+pub async fn reverse_experience(
+    db: &Database,
+    entry_id: ObjectId,
+    admin: &str,
+    reason: &str,
+) -> Result<Option<i64>, String> {
+    let ledger = db.collection::<Document>("user_exp");
+
+    let entry = ledger
+        .find_one(
+            doc! {
+                "_id": entry_id,
+                "reversed": { "$ne": true },
+                "source.type": { "$ne": "reversal" },
+            },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error querying ledger entry: {}", e))?;
+    let Some(entry) = entry else {
+        return Ok(None);
+    };
+    let (Ok(address), Some(experience), Some(timestamp)) = (
+        entry.get_str("address"),
+        get_number(&entry, "experience"),
+        get_number(&entry, "timestamp"),
+    ) else {
+        return Err(format!("Invalid ledger entry {}", entry_id));
+    };
+    let experience = experience as i64;
+
+    let source = XpSource::Reversal {
+        entry_id,
+        admin: admin.to_string(),
+        reason: reason.to_string(),
+    };
+    let reversal = doc! {
+        "address": address,
+        "experience": -experience,
+        "timestamp": timestamp,
+        "reversed_at": Utc::now().timestamp_millis(),
+        "source": to_bson(&source).map_err(|e| format!("Error serializing source: {}", e))?,
+    };
+    let is_new_reversal = match ledger.insert_one(reversal, None).await {
+        Ok(_) => true,
+        Err(e) if is_duplicate_key(&e) => false,
+        Err(e) => return Err(format!("Error saving reversal: {}", e)),
+    };
+
+    // leaderboards are only decremented by the call recording the reversal, if that fails they
+    // are corrected when rebuilt from the ledger on startup. The leaderboard keeps the time of
+    // the last experience earned, so only totals are changed
+    if is_new_reversal {
+        db.collection::<Document>("leaderboard_table")
+            .update_one(
+                doc! { "_id": address },
+                doc! { "$inc": { "experience": -experience } },
+                None,
+            )
+            .await
+            .map_err(|e| format!("Error updating leaderboard: {}", e))?;
+        db.collection::<Document>("leaderboard_daily")
+            .update_one(
+                doc! { "address": address, "day": get_day_bucket(timestamp as i64) },
+                doc! { "$inc": { "experience": -experience } },
+                None,
+            )
+            .await
+            .map_err(|e| format!("Error updating leaderboard: {}", e))?;
+    }
+
+    let flagged = ledger
+        .update_one(
+            doc! { "_id": entry_id, "reversed": { "$ne": true } },
+            doc! { "$set": { "reversed": true } },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error flagging ledger entry: {}", e))?;
+    Ok((flagged.modified_count == 1).then_some(experience))
+}
+
+// reverses every active ledger entry of an address, returning the experience removed
+pub async fn reverse_address_experience(
+    db: &Database,
+    address: &str,
+    admin: &str,
+    reason: &str,
+) -> Result<i64, String> {
+    let entries: Vec<Document> = db
+        .collection::<Document>("user_exp")
+        .find(
+            doc! {
+                "address": address,
+                "reversed": { "$ne": true },
+                "source.type": { "$ne": "reversal" },
+            },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error querying ledger: {}", e))?
+        .try_collect()
+        .await
+        .map_err(|e| format!("Error querying ledger: {}", e))?;
+
+    let mut total = 0;
+    for entry in entries {
+        let Ok(entry_id) = entry.get_object_id("_id") else {
+            continue;
+        };
+        if let Some(experience) = reverse_experience(db, entry_id, admin, reason).await? {
+            total += experience;
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::join_all;
+    use mongodb::Client;
+
+    // needs a running mongodb: MONGODB_TEST_URI=mongodb://localhost:27017 cargo test -- --ignored
+    #[tokio::test]
+    #[ignore]
+    async fn repeated_achievement_credits_once() {
+        let uri = std::env::var("MONGODB_TEST_URI")
+            .unwrap_or_else(|_| "mongodb://localhost:27017".to_string());
+        let db = Client::with_uri_str(&uri)
+            .await
+            .unwrap()
+            .database(&format!("xp_ledger_test_{}", Utc::now().timestamp_millis()));
+        add_ledger_indexes(&db).await;
+
+        // the same achievement is credited ten times at once, then once more
+        let credits = (0..10).map(|_| {
+            let db = db.clone();
+            tokio::spawn(async move { credit_achievement(&db, "0x1".to_string(), 1, 50).await })
+        });
+        let credited = join_all(credits)
+            .await
+            .into_iter()
+            .filter(|result| *result.as_ref().unwrap().as_ref().unwrap())
+            .count();
+        assert_eq!(credited, 1);
+        assert!(!credit_achievement(&db, "0x1".to_string(), 1, 50)
+            .await
+            .unwrap());
+        // another achievement or another address is still credited
+        assert!(credit_achievement(&db, "0x1".to_string(), 2, 20)
+            .await
+            .unwrap());
+        assert!(credit_achievement(&db, "0x2".to_string(), 1, 50)
+            .await
+            .unwrap());
+
+        let ledger = db.collection::<Document>("user_exp");
+        let filter = doc! { "address": "0x1", "source.achievement_id": 1 };
+        assert_eq!(ledger.count_documents(filter, None).await.unwrap(), 1);
+        let leaderboard = db.collection::<Document>("leaderboard_table");
+        let entry = leaderboard
+            .find_one(doc! { "_id": "0x1" }, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(get_number(&entry, "experience"), Some(70.0));
+
+        db.drop(None).await.unwrap();
+    }
+}
@@ -41,6 +41,7 @@ pub_struct!(Clone, Deserialize; StarknetIdContracts {
     naming_contract: FieldElement,
     verifier_contracts: Vec<FieldElement>,
     identity_contract: FieldElement,
+    pfp_verifier_contract: Option<FieldElement>,
 });
 
 pub_struct!(Clone, Deserialize;  NamingContract { address: String });
@@ -110,11 +111,30 @@ pub_struct!(Clone, Deserialize;  Twitter {
 pub_struct!(Clone, Deserialize;  QuestBoost{
     private_key: FieldElement,
     update_interval: u64,
+    // claims are only indexed once the contract and the block it was deployed at are set
+    contract: Option<FieldElement>,
+    claims_start_block: Option<u64>,
+    claims_update_interval: Option<u64>,
+    // whether the boost contract keys claims by token, a contract accepting a single claim per
+    // boost and address only paying the main token of a boost
+    multi_token_claims: Option<bool>,
 });
 
 pub_struct!(Clone, Deserialize;  Discord {
     oauth2_clientid: String,
     oauth2_secret: String,
+    // reads the roles of members when set, instead of requiring the guilds.members.read scope
+    bot_token: Option<String>,
+});
+
+pub_struct!(Clone, Deserialize;  Telegram {
+    // the bot must be a member of the chats of telegram tasks
+    bot_token: String,
+});
+
+pub_struct!(Clone, Deserialize;  Github {
+    oauth2_clientid: String,
+    oauth2_secret: String,
 });
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -180,12 +200,32 @@ pub_struct!(Clone, Deserialize;  Achievements {
     braavos: Achievement,
     argent: Achievement,
     carbonable: Achievement,
+    // claims are only indexed once the block the nft contract was deployed at is set
+    claims_start_block: Option<u64>,
+    claims_update_interval: Option<u64>,
 });
 
 pub_struct!(Clone, Deserialize;  AuthSetup {
     secret_key: String,
     expiry_duration: i64,
-});
+    session_secret_key: Option<String>,
+    // seconds a wallet session lasts
+    session_expiry_duration: Option<i64>,
+});
+
+impl AuthSetup {
+    // derived from the admin secret when not set, keeping admin and session tokens signed with
+    // different keys so that one is never accepted as the other
+    pub fn get_session_secret_key(&self) -> String {
+        self.session_secret_key
+            .clone()
+            .unwrap_or_else(|| format!("{}:session", self.secret_key))
+    }
+
+    pub fn get_session_expiry_duration(&self) -> i64 {
+        self.session_expiry_duration.unwrap_or(3600)
+    }
+}
 
 pub_struct!(Clone, Deserialize;  ProtocolStats {
     pairs_api_endpoint: String,
@@ -206,8 +246,19 @@ pub_struct!(Clone, Deserialize;  Token {
 
 pub_struct!(Clone, Deserialize;  Tokens {
     strk: Token,
+    registry: Option<Vec<Token>>,
 });
 
+impl Tokens {
+    // looks a token up by symbol or contract address, strk being part of the registry
+    pub fn find(&self, token: &str) -> Option<&Token> {
+        let contract = FieldElement::from_hex_be(token).ok();
+        std::iter::once(&self.strk)
+            .chain(self.registry.iter().flatten())
+            .find(|t| t.symbol.eq_ignore_ascii_case(token) || Some(t.contract) == contract)
+    }
+}
+
 pub_struct!(Clone, Deserialize;  Config {
     server: Server,
     database: Database,
@@ -218,6 +269,8 @@ pub_struct!(Clone, Deserialize;  Config {
     quests: Quests,
     twitter: Twitter,
     discord: Discord,
+    github: Option<Github>,
+    telegram: Option<Telegram>,
     starkscan: Starkscan,
     achievements: Achievements,
     watchtower: Watchtower,
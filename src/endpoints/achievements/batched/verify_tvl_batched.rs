@@ -1,9 +1,10 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::{
-    common::get_achievement::get_achievement,
+    common::{achievement_verifiers::get_api_value, get_achievement::get_achievement},
     models::{AppState, VerifyAchievementBatchedQuery},
-    utils::{get_error, to_hex, AchievementsTrait},
+    utils::{get_error, AchievementsTrait},
 };
 use axum::{
     extract::{Query, State},
@@ -14,8 +15,9 @@ use axum::{
 use axum_auto_routes::route;
 use serde_json::json;
 use starknet::core::types::FieldElement;
+use std::collections::HashMap;
 
-#[route(get, "/achievements/batched/verify_tvl_batched")]
+#[route(get, "/achievements/batched/verify_tvl_batched", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyAchievementBatchedQuery>,
@@ -25,52 +27,53 @@ pub async fn handler(
         return get_error("Please connect your wallet first".to_string());
     }
 
-    let url = format!(
-        "https://public.starkendefi.xyz/public/aggregates/{}",
-        to_hex(addr)
-    );
-    let client = reqwest::Client::new();
-    match client.get(url).send().await {
-        Ok(response) => match response.json::<serde_json::Value>().await {
-            Ok(json) => {
-                if let Some(total_tvl_dollars) = json["total_tvl_dollars"].as_f64() {
-                    if total_tvl_dollars < 100.0 {
-                        return get_error("Your TVL is too low".to_string());
-                    }
+    let achievements = match get_achievement(&state, &query.addr, query.category_id).await {
+        Ok(achievements) => achievements.achievements,
+        Err(e) => return get_error(e),
+    };
 
-                    match get_achievement(&state, &query.addr, query.category_id).await {
-                        Ok(achievements) => {
-                            let mut achieved: Vec<u32> = vec![];
-                            for achievement in achievements.achievements {
-                                if !achievement.completed
-                                    && ((achievement.id == 11 && total_tvl_dollars >= 100.0)
-                                        || (achievement.id == 12 && total_tvl_dollars >= 1000.0)
-                                        || (achievement.id == 13 && total_tvl_dollars >= 10000.0))
-                                {
-                                    match state
-                                        .upsert_completed_achievement(addr, achievement.id)
-                                        .await
-                                    {
-                                        Ok(_) => {
-                                            achieved.push(achievement.id);
-                                        }
-                                        Err(e) => return get_error(format!("{}", e)),
-                                    }
-                                }
-                            }
-                            (StatusCode::OK, Json(json!({ "achieved": achieved }))).into_response()
-                        }
-                        Err(e) => get_error(e),
-                    }
-                } else {
-                    get_error("total_tvl_dollars not found or not a float".to_string())
+    // tiers of the category are reached by their own threshold, each source being queried once
+    let mut values: HashMap<(String, String), f64> = HashMap::new();
+    let mut achieved: Vec<u32> = vec![];
+    let mut has_reached_tier = false;
+    for achievement in achievements {
+        let Some(params) = &achievement.verify_params else {
+            continue;
+        };
+        let (Some(url), Some(field)) = (&params.source_url, &params.source_field) else {
+            continue;
+        };
+        let value = match values.get(&(url.clone(), field.clone())) {
+            Some(value) => *value,
+            None => match get_api_value(params, &addr).await {
+                Ok(value) => {
+                    values.insert((url.clone(), field.clone()), value);
+                    value
                 }
-            }
-            Err(e) => get_error(format!(
-                "Failed to get JSON response from Starkendefi: {}",
-                e
-            )),
-        },
-        Err(e) => get_error(format!("Failed to fetch Starkendefi: {}", e)),
+                Err(e) => return get_error(e),
+            },
+        };
+        if value < params.threshold {
+            continue;
+        }
+        has_reached_tier = true;
+        if achievement.completed {
+            continue;
+        }
+        match state
+            .upsert_completed_achievement(addr, achievement.id)
+            .await
+        {
+            Ok(_) => achieved.push(achievement.id),
+            Err(e) => return get_error(format!("{}", e)),
+        }
     }
+
+    if values.is_empty() {
+        return get_error("This category has no achievement to verify".to_string());
+    }
+    if !has_reached_tier {
+        return get_error("Your TVL is too low".to_string());
+    }
+    (StatusCode::OK, Json(json!({ "achieved": achieved }))).into_response()
 }
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::common::achievement_verifiers::get_threshold_achievement;
 use crate::utils::{to_hex, AchievementsTrait};
 use crate::{
     models::{AppState, VerifyAchievementQuery},
@@ -16,17 +17,6 @@ use futures::TryStreamExt;
 use mongodb::bson::{doc, Document};
 use serde_json::json;
 use starknet::core::types::FieldElement;
-fn get_number_of_quests(id: u32) -> u32 {
-    return match id {
-        23 => 1,
-        24 => 3,
-        25 => 10,
-        26 => 25,
-        27 => 50,
-        _ => 0,
-    };
-}
-
 #[route(get, "/achievements/claim/quest_achievement")]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
@@ -38,12 +28,11 @@ pub async fn handler(
     }
 
     let achievement_id = query.id;
-    let quests_threshold = get_number_of_quests(achievement_id);
-
-    // check valid achievement id
-    if !(23..=27).contains(&achievement_id) {
-        return get_error("Invalid achievement id".to_string());
-    }
+    let quests_threshold =
+        match get_threshold_achievement(&state, achievement_id, Some("quests")).await {
+            Ok((_, params)) => params.threshold,
+            Err(e) => return get_error(e),
+        };
 
     let pipeline = vec![
         doc! {
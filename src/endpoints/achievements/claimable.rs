@@ -0,0 +1,99 @@
+use std::sync::Arc;
+
+use crate::models::{AchievementDocument, AchievementReward, AppState};
+use crate::utils::{get_achievement_nft, get_error, to_hex};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_auto_routes::route;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Bson, Document};
+use serde::Deserialize;
+use serde_json::json;
+use starknet::{
+    core::types::FieldElement,
+    signers::{LocalWallet, SigningKey},
+};
+
+#[derive(Deserialize)]
+pub struct ClaimableQuery {
+    addr: FieldElement,
+}
+
+// signs a mint for every achievement reached by the address that has an nft and was not claimed
+// yet, claims being recorded once the achievement claims indexer sees them on chain
+#[route(get, "/achievements/claimable")]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ClaimableQuery>,
+) -> impl IntoResponse {
+    let addr = query.addr;
+    if addr == FieldElement::ZERO {
+        return get_error("Please connect your wallet first".to_string());
+    }
+
+    let achieved: Vec<Bson> = match state
+        .db
+        .collection::<Document>("achieved")
+        .distinct("achievement_id", doc! { "addr": addr.to_string() }, None)
+        .await
+    {
+        Ok(achieved) => achieved,
+        Err(_) => return get_error("Error querying achievements".to_string()),
+    };
+    let claimed: Vec<Bson> = match state
+        .db
+        .collection::<Document>("claimed_achievements")
+        .distinct("id", doc! { "address": to_hex(addr) }, None)
+        .await
+    {
+        Ok(claimed) => claimed,
+        Err(_) => return get_error("Error querying claimed achievements".to_string()),
+    };
+
+    let filter = doc! {
+        "$and": [
+            { "id": { "$in": achieved } },
+            { "id": { "$nin": claimed } },
+        ],
+        "nft_level": { "$ne": null },
+    };
+    let achievements: Vec<AchievementDocument> = match state
+        .db
+        .collection::<AchievementDocument>("achievements")
+        .find(filter, None)
+        .await
+    {
+        Ok(cursor) => match cursor.try_collect().await {
+            Ok(achievements) => achievements,
+            Err(_) => return get_error("Error querying achievements".to_string()),
+        },
+        Err(_) => return get_error("Error querying achievements".to_string()),
+    };
+
+    let signer = LocalWallet::from(SigningKey::from_secret_scalar(
+        state.conf.nft_contract.private_key,
+    ));
+    let mut rewards = vec![];
+    for achievement in achievements {
+        let Some(nft_level) = achievement.nft_level else {
+            continue;
+        };
+        let Ok((token_id, sig)) =
+            get_achievement_nft(achievement.id, &addr, nft_level, &signer).await
+        else {
+            return get_error("Signature failed".into());
+        };
+        rewards.push(AchievementReward {
+            achievement_id: achievement.id,
+            nft_contract: state.conf.nft_contract.address.clone(),
+            token_id: token_id.to_string(),
+            sig: (sig.r, sig.s),
+        });
+    }
+
+    (StatusCode::OK, Json(json!({ "rewards": rewards }))).into_response()
+}
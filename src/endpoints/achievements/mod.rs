@@ -1,7 +1,9 @@
 pub mod batched;
 pub mod claim;
+pub mod claimable;
 pub mod fetch;
 pub mod fetch_buildings;
+pub mod verify;
 pub mod verify_achieved_quests;
 pub mod verify_avnu;
 pub mod verify_briq;
@@ -0,0 +1,34 @@
+use crate::middleware::session::session_middleware;
+use std::sync::Arc;
+
+use crate::{
+    common::achievement_verifiers::verify_achievement,
+    models::{AppState, VerifyAchievementQuery},
+    utils::get_error,
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_auto_routes::route;
+use serde_json::json;
+use starknet::core::types::FieldElement;
+
+// verifies any achievement checked against the threshold of its verify_params
+#[route(get, "/achievements/verify", session_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<VerifyAchievementQuery>,
+) -> impl IntoResponse {
+    let addr = query.addr;
+    if addr == FieldElement::ZERO {
+        return get_error("Please connect your wallet first".to_string());
+    }
+
+    match verify_achievement(&state, addr, query.id).await {
+        Ok(_) => (StatusCode::OK, Json(json!({"achieved": true}))).into_response(),
+        Err(e) => get_error(e),
+    }
+}
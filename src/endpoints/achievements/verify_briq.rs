@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::utils::fetch_json_from_url;
@@ -16,7 +17,7 @@ use mongodb::bson::doc;
 use serde_json::json;
 use starknet::core::types::FieldElement;
 
-#[route(get, "/achievements/verify_briq")]
+#[route(get, "/achievements/verify_briq", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyAchievementQuery>,
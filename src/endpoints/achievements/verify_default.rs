@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::{
@@ -42,7 +43,7 @@ fn get_args(config: Config, achievement_id: u32) -> Result<(FieldElement, u32, N
     }
 }
 
-#[route(get, "/achievements/verify_default")]
+#[route(get, "/achievements/verify_default", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyAchievementQuery>,
@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use crate::{
     models::{AppState, VerifyQuery},
     utils::{get_error, AchievementsTrait},
@@ -20,7 +21,7 @@ use std::{
     time::{SystemTime, UNIX_EPOCH},
 };
 
-#[route(get, "/achievements/verify_has_domain")]
+#[route(get, "/achievements/verify_has_domain", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyQuery>,
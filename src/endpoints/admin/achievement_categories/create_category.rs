@@ -0,0 +1,68 @@
+use crate::middleware::auth::auth_middleware;
+use crate::models::AchievementCategoryDocument;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOneOptions;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+pub_struct!(Deserialize; CreateCategoryQuery {
+    name: String,
+    desc: String,
+    img_url: String,
+    category_type: Option<String>,
+    disabled: Option<bool>,
+    override_verified_type: Option<String>,
+});
+
+#[route(post, "/admin/achievement_categories/create", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<CreateCategoryQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    let categories_collection = state
+        .db
+        .collection::<AchievementCategoryDocument>("achievement_categories");
+    // held until the insert so that concurrent creates don't pick the same id
+    let _state_last_id = state.last_task_id.lock().await;
+    let options = FindOneOptions::builder().sort(doc! {"id": -1}).build();
+    let id = match categories_collection.find_one(doc! {}, options).await {
+        Ok(Some(category)) => category.id + 1,
+        Ok(None) => 1,
+        Err(_) => return get_error("Error querying categories".to_string()),
+    };
+
+    // type, disabled and override_verified_type are only read by the frontend
+    let mut new_document = doc! {
+        "id": id,
+        "name": body.name,
+        "desc": body.desc,
+        "img_url": body.img_url,
+    };
+    if let Some(category_type) = body.category_type {
+        new_document.insert("type", category_type);
+    }
+    if let Some(disabled) = body.disabled {
+        new_document.insert("disabled", disabled);
+    }
+    if let Some(override_verified_type) = body.override_verified_type {
+        new_document.insert("override_verified_type", override_verified_type);
+    }
+    let collection = state.db.collection::<Document>("achievement_categories");
+    match collection.insert_one(new_document, None).await {
+        Ok(_) => (StatusCode::OK, Json(json!({"id": id}))).into_response(),
+        Err(_) => get_error("Error creating category".to_string()),
+    }
+}
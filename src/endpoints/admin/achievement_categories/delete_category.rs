@@ -0,0 +1,52 @@
+use crate::middleware::auth::auth_middleware;
+use crate::models::{AchievementCategoryDocument, AchievementDocument};
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::doc;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+pub_struct!(Deserialize; DeleteCategoryQuery {
+    id: u32,
+});
+
+#[route(post, "/admin/achievement_categories/delete", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<DeleteCategoryQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    // achievements would be orphaned, so they have to be moved or deleted first
+    let achievements_collection = state.db.collection::<AchievementDocument>("achievements");
+    match achievements_collection
+        .count_documents(doc! {"category_id": body.id}, None)
+        .await
+    {
+        Ok(0) => {}
+        Ok(_) => return get_error("Category still has achievements".to_string()),
+        Err(_) => return get_error("Error querying achievements".to_string()),
+    }
+
+    let collection = state
+        .db
+        .collection::<AchievementCategoryDocument>("achievement_categories");
+    match collection.delete_one(doc! {"id": body.id}, None).await {
+        Ok(result) if result.deleted_count == 0 => get_error("Category does not exist".to_string()),
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "deleted successfully"})),
+        )
+            .into_response(),
+        Err(_) => get_error("Error deleting category".to_string()),
+    }
+}
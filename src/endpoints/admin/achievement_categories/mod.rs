@@ -0,0 +1,3 @@
+pub mod create_category;
+pub mod delete_category;
+pub mod update_category;
@@ -0,0 +1,67 @@
+use crate::middleware::auth::auth_middleware;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+pub_struct!(Deserialize; UpdateCategoryQuery {
+    id: u32,
+    name: Option<String>,
+    desc: Option<String>,
+    img_url: Option<String>,
+    category_type: Option<String>,
+    disabled: Option<bool>,
+    override_verified_type: Option<String>,
+});
+
+#[route(post, "/admin/achievement_categories/update", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<UpdateCategoryQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    let mut update_doc = Document::new();
+    if let Some(name) = &body.name {
+        update_doc.insert("name", name);
+    }
+    if let Some(desc) = &body.desc {
+        update_doc.insert("desc", desc);
+    }
+    if let Some(img_url) = &body.img_url {
+        update_doc.insert("img_url", img_url);
+    }
+    if let Some(category_type) = &body.category_type {
+        update_doc.insert("type", category_type);
+    }
+    if let Some(disabled) = body.disabled {
+        update_doc.insert("disabled", disabled);
+    }
+    if let Some(override_verified_type) = &body.override_verified_type {
+        update_doc.insert("override_verified_type", override_verified_type);
+    }
+
+    let collection = state.db.collection::<Document>("achievement_categories");
+    match collection
+        .update_one(doc! {"id": body.id}, doc! {"$set": update_doc}, None)
+        .await
+    {
+        Ok(result) if result.matched_count == 0 => get_error("Category does not exist".to_string()),
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "updated successfully"})),
+        )
+            .into_response(),
+        Err(_) => get_error("Error updating category".to_string()),
+    }
+}
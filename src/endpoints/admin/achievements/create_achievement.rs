@@ -0,0 +1,88 @@
+use crate::common::achievement_verifiers::{validate_verify_params, validate_verify_type};
+use crate::middleware::auth::auth_middleware;
+use crate::models::{AchievementCategoryDocument, AchievementDocument, AchievementVerifyParams};
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::doc;
+use mongodb::options::FindOneOptions;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+pub_struct!(Deserialize; CreateAchievementQuery {
+    category_id: u32,
+    name: String,
+    img_url: String,
+    short_desc: String,
+    todo_title: String,
+    todo_desc: String,
+    done_title: String,
+    done_desc: String,
+    verify_type: String,
+    experience: i64,
+    verify_params: Option<AchievementVerifyParams>,
+    nft_level: Option<u32>,
+});
+
+#[route(post, "/admin/achievements/create", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<CreateAchievementQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+    if let Err(e) = validate_verify_type(&body.verify_type)
+        .and_then(|_| validate_verify_params(&body.verify_type, &body.verify_params))
+    {
+        return get_error(e);
+    }
+
+    let categories_collection = state
+        .db
+        .collection::<AchievementCategoryDocument>("achievement_categories");
+    match categories_collection
+        .find_one(doc! {"id": body.category_id}, None)
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return get_error("Category does not exist".to_string()),
+        Err(_) => return get_error("Error querying categories".to_string()),
+    }
+
+    // held until the insert so that concurrent creates don't pick the same id
+    let _state_last_id = state.last_task_id.lock().await;
+    let collection = state.db.collection::<AchievementDocument>("achievements");
+    let options = FindOneOptions::builder().sort(doc! {"id": -1}).build();
+    let id = match collection.find_one(doc! {}, options).await {
+        Ok(Some(achievement)) => achievement.id + 1,
+        Ok(None) => 1,
+        Err(_) => return get_error("Error querying achievements".to_string()),
+    };
+
+    let new_document = AchievementDocument {
+        id,
+        category_id: body.category_id,
+        name: body.name,
+        img_url: body.img_url,
+        short_desc: body.short_desc,
+        todo_title: body.todo_title,
+        todo_desc: body.todo_desc,
+        done_title: body.done_title,
+        done_desc: body.done_desc,
+        verify_type: body.verify_type,
+        experience: body.experience,
+        verify_params: body.verify_params,
+        nft_level: body.nft_level,
+    };
+    match collection.insert_one(new_document, None).await {
+        Ok(_) => (StatusCode::OK, Json(json!({"id": id}))).into_response(),
+        Err(_) => get_error("Error creating achievement".to_string()),
+    }
+}
@@ -0,0 +1,41 @@
+use crate::middleware::auth::auth_middleware;
+use crate::models::AchievementDocument;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::doc;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+pub_struct!(Deserialize; DeleteAchievementQuery {
+    id: u32,
+});
+
+#[route(post, "/admin/achievements/delete", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<DeleteAchievementQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    let collection = state.db.collection::<AchievementDocument>("achievements");
+    match collection.delete_one(doc! {"id": body.id}, None).await {
+        Ok(result) if result.deleted_count == 0 => {
+            get_error("Achievement does not exist".to_string())
+        }
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "deleted successfully"})),
+        )
+            .into_response(),
+        Err(_) => get_error("Error deleting achievement".to_string()),
+    }
+}
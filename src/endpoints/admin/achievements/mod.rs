@@ -0,0 +1,3 @@
+pub mod create_achievement;
+pub mod delete_achievement;
+pub mod update_achievement;
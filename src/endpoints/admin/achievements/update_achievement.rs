@@ -0,0 +1,124 @@
+use crate::common::achievement_verifiers::{validate_verify_params, validate_verify_type};
+use crate::middleware::auth::auth_middleware;
+use crate::models::{AchievementCategoryDocument, AchievementDocument, AchievementVerifyParams};
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::{doc, to_bson, Document};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+pub_struct!(Deserialize; UpdateAchievementQuery {
+    id: u32,
+    category_id: Option<u32>,
+    name: Option<String>,
+    img_url: Option<String>,
+    short_desc: Option<String>,
+    todo_title: Option<String>,
+    todo_desc: Option<String>,
+    done_title: Option<String>,
+    done_desc: Option<String>,
+    verify_type: Option<String>,
+    experience: Option<i64>,
+    verify_params: Option<AchievementVerifyParams>,
+    nft_level: Option<u32>,
+});
+
+#[route(post, "/admin/achievements/update", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<UpdateAchievementQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    let collection = state.db.collection::<AchievementDocument>("achievements");
+    let achievement = match collection.find_one(doc! {"id": body.id}, None).await {
+        Ok(Some(achievement)) => achievement,
+        Ok(None) => return get_error("Achievement does not exist".to_string()),
+        Err(_) => return get_error("Error querying achievement".to_string()),
+    };
+
+    if let Some(category_id) = body.category_id {
+        let categories_collection = state
+            .db
+            .collection::<AchievementCategoryDocument>("achievement_categories");
+        match categories_collection
+            .find_one(doc! {"id": category_id}, None)
+            .await
+        {
+            Ok(Some(_)) => {}
+            Ok(None) => return get_error("Category does not exist".to_string()),
+            Err(_) => return get_error("Error querying categories".to_string()),
+        }
+    }
+
+    // the verifier is validated against the values it ends up with
+    if let Some(verify_type) = &body.verify_type {
+        if let Err(e) = validate_verify_type(verify_type) {
+            return get_error(e);
+        }
+    }
+    let verify_type = body.verify_type.clone().unwrap_or(achievement.verify_type);
+    let verify_params = body.verify_params.clone().or(achievement.verify_params);
+    if let Err(e) = validate_verify_params(&verify_type, &verify_params) {
+        return get_error(e);
+    }
+
+    let mut update_doc = Document::new();
+    if let Some(category_id) = body.category_id {
+        update_doc.insert("category_id", category_id);
+    }
+    if let Some(name) = &body.name {
+        update_doc.insert("name", name);
+    }
+    if let Some(img_url) = &body.img_url {
+        update_doc.insert("img_url", img_url);
+    }
+    if let Some(short_desc) = &body.short_desc {
+        update_doc.insert("short_desc", short_desc);
+    }
+    if let Some(todo_title) = &body.todo_title {
+        update_doc.insert("todo_title", todo_title);
+    }
+    if let Some(todo_desc) = &body.todo_desc {
+        update_doc.insert("todo_desc", todo_desc);
+    }
+    if let Some(done_title) = &body.done_title {
+        update_doc.insert("done_title", done_title);
+    }
+    if let Some(done_desc) = &body.done_desc {
+        update_doc.insert("done_desc", done_desc);
+    }
+    if let Some(verify_type) = &body.verify_type {
+        update_doc.insert("verify_type", verify_type);
+    }
+    if let Some(experience) = body.experience {
+        update_doc.insert("experience", experience);
+    }
+    if let Some(verify_params) = &body.verify_params {
+        update_doc.insert("verify_params", to_bson(verify_params).unwrap());
+    }
+    if let Some(nft_level) = body.nft_level {
+        update_doc.insert("nft_level", nft_level);
+    }
+
+    match collection
+        .update_one(doc! {"id": body.id}, doc! {"$set": update_doc}, None)
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "updated successfully"})),
+        )
+            .into_response(),
+        Err(_) => get_error("Error updating achievement".to_string()),
+    }
+}
@@ -1,5 +1,6 @@
+use crate::common::block_sampling::validate_block_sampling;
 use crate::middleware::auth::auth_middleware;
-use crate::models::{QuestDocument, QuestTaskDocument};
+use crate::models::{BlockSampling, QuestDocument, QuestTaskDocument};
 use crate::utils::get_next_task_id;
 use crate::utils::verify_quest_auth;
 use crate::{models::AppState, utils::get_error};
@@ -23,6 +24,7 @@ pub_struct!(Deserialize; CreateBalance {
     contracts: String,
     href: String,
     cta: String,
+    block_sampling: Option<BlockSampling>,
 });
 
 #[route(post, "/admin/tasks/balance/create", auth_middleware)]
@@ -40,6 +42,12 @@ pub async fn handler(
         return get_error("Error creating task".to_string());
     };
 
+    if let Some(block_sampling) = &body.block_sampling {
+        if let Err(e) = validate_block_sampling(block_sampling) {
+            return get_error(format!("Invalid block sampling: {}", e));
+        }
+    }
+
     let state_last_id = state.last_task_id.lock().await;
 
     let next_id = get_next_task_id(&collection, state_last_id.clone()).await;
@@ -69,6 +77,8 @@ pub async fn handler(
         api_url: None,
         regex: None,
         calls: None,
+        rule: None,
+        block_sampling: body.block_sampling.clone(),
     };
 
     // insert document to boost collection
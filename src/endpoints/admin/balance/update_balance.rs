@@ -1,5 +1,6 @@
+use crate::common::block_sampling::validate_block_sampling;
 use crate::middleware::auth::auth_middleware;
-use crate::models::QuestTaskDocument;
+use crate::models::{BlockSampling, QuestTaskDocument};
 use crate::utils::verify_task_auth;
 use crate::{models::AppState, utils::get_error};
 
@@ -9,7 +10,7 @@ use axum::{
     response::{IntoResponse, Json},
 };
 use axum_auto_routes::route;
-use mongodb::bson::doc;
+use mongodb::bson::{doc, to_bson};
 use serde::Deserialize;
 use serde_json::json;
 use starknet::core::types::FieldElement;
@@ -23,6 +24,7 @@ pub_struct!(Deserialize; CreateBalance {
     contracts: Option<String>,
     href: Option<String>,
     cta: Option<String>,
+    block_sampling: Option<BlockSampling>,
 });
 
 // Helper function to convert FieldElement to Bson
@@ -72,6 +74,13 @@ pub async fn handler(
         update_doc.insert("contracts", contracts_bson);
     }
 
+    if let Some(block_sampling) = &body.block_sampling {
+        if let Err(e) = validate_block_sampling(block_sampling) {
+            return get_error(format!("Invalid block sampling: {}", e));
+        }
+        update_doc.insert("block_sampling", to_bson(block_sampling).unwrap());
+    }
+
     // update quest query
     let update = doc! {
         "$set": update_doc
@@ -0,0 +1,58 @@
+use crate::middleware::auth::auth_middleware;
+use crate::models::BuildingDocument;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::doc;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+// building ids are referenced by the nfts of the land, so they are chosen by the admin
+pub_struct!(Deserialize; CreateBuildingQuery {
+    id: u32,
+    name: String,
+    description: String,
+    entity: String,
+    level: u32,
+    img_url: String,
+});
+
+#[route(post, "/admin/buildings/create", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<CreateBuildingQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    let collection = state.db.collection::<BuildingDocument>("buildings");
+    match collection.find_one(doc! {"id": body.id}, None).await {
+        Ok(None) => {}
+        Ok(Some(_)) => return get_error(format!("Building {} already exists", body.id)),
+        Err(_) => return get_error("Error querying buildings".to_string()),
+    }
+
+    let new_document = BuildingDocument {
+        id: body.id,
+        name: body.name,
+        description: body.description,
+        entity: body.entity,
+        level: body.level,
+        img_url: body.img_url,
+    };
+    match collection.insert_one(new_document, None).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "Building added successfully"})),
+        )
+            .into_response(),
+        Err(_) => get_error("Error creating building".to_string()),
+    }
+}
@@ -0,0 +1,39 @@
+use crate::middleware::auth::auth_middleware;
+use crate::models::BuildingDocument;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::doc;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+pub_struct!(Deserialize; DeleteBuildingQuery {
+    id: u32,
+});
+
+#[route(post, "/admin/buildings/delete", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<DeleteBuildingQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    let collection = state.db.collection::<BuildingDocument>("buildings");
+    match collection.delete_one(doc! {"id": body.id}, None).await {
+        Ok(result) if result.deleted_count == 0 => get_error("Building does not exist".to_string()),
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "deleted successfully"})),
+        )
+            .into_response(),
+        Err(_) => get_error("Error deleting building".to_string()),
+    }
+}
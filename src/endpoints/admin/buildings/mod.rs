@@ -0,0 +1,3 @@
+pub mod create_building;
+pub mod delete_building;
+pub mod update_building;
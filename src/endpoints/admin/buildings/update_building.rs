@@ -0,0 +1,64 @@
+use crate::middleware::auth::auth_middleware;
+use crate::models::BuildingDocument;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::{doc, Document};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+pub_struct!(Deserialize; UpdateBuildingQuery {
+    id: u32,
+    name: Option<String>,
+    description: Option<String>,
+    entity: Option<String>,
+    level: Option<u32>,
+    img_url: Option<String>,
+});
+
+#[route(post, "/admin/buildings/update", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<UpdateBuildingQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    let mut update_doc = Document::new();
+    if let Some(name) = &body.name {
+        update_doc.insert("name", name);
+    }
+    if let Some(description) = &body.description {
+        update_doc.insert("description", description);
+    }
+    if let Some(entity) = &body.entity {
+        update_doc.insert("entity", entity);
+    }
+    if let Some(level) = body.level {
+        update_doc.insert("level", level);
+    }
+    if let Some(img_url) = &body.img_url {
+        update_doc.insert("img_url", img_url);
+    }
+
+    let collection = state.db.collection::<BuildingDocument>("buildings");
+    match collection
+        .update_one(doc! {"id": body.id}, doc! {"$set": update_doc}, None)
+        .await
+    {
+        Ok(result) if result.matched_count == 0 => get_error("Building does not exist".to_string()),
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "updated successfully"})),
+        )
+            .into_response(),
+        Err(_) => get_error("Error updating building".to_string()),
+    }
+}
@@ -1,5 +1,7 @@
+use crate::common::block_sampling::validate_block_sampling;
+use crate::common::verification_rules::validate_call;
 use crate::middleware::auth::auth_middleware;
-use crate::models::{Call, QuestDocument, QuestTaskDocument};
+use crate::models::{BlockSampling, Call, QuestDocument, QuestTaskDocument};
 use crate::utils::get_next_task_id;
 use crate::utils::verify_quest_auth;
 use crate::{models::AppState, utils::get_error};
@@ -21,7 +23,8 @@ pub_struct!(Deserialize; CreateContract {
     desc: String,
     href: String,
     cta: String,
-    calls: Vec<Call>
+    calls: Vec<Call>,
+    block_sampling: Option<BlockSampling>,
 });
 
 #[route(post, "/admin/tasks/contract/create", auth_middleware)]
@@ -39,6 +42,16 @@ pub async fn handler(
         return get_error("Error creating task".to_string());
     };
 
+    if let Some(block_sampling) = &body.block_sampling {
+        if let Err(e) = validate_block_sampling(block_sampling) {
+            return get_error(format!("Invalid block sampling: {}", e));
+        }
+    }
+
+    if let Err(e) = body.calls.iter().try_for_each(validate_call) {
+        return get_error(format!("Invalid call: {}", e));
+    }
+
     let state_last_id = state.last_task_id.lock().await;
 
     let next_id = get_next_task_id(&collection, state_last_id.clone()).await;
@@ -61,6 +74,8 @@ pub async fn handler(
         calls: Some(body.calls),
         api_url: None,
         regex: None,
+        rule: None,
+        block_sampling: body.block_sampling.clone(),
     };
 
     // insert document to boost collection
@@ -1,5 +1,7 @@
+use crate::common::block_sampling::validate_block_sampling;
+use crate::common::verification_rules::validate_call;
 use crate::middleware::auth::auth_middleware;
-use crate::models::{Call, QuestTaskDocument};
+use crate::models::{BlockSampling, Call, QuestTaskDocument};
 use crate::utils::verify_task_auth;
 use crate::{models::AppState, utils::get_error};
 
@@ -20,7 +22,8 @@ pub_struct!(Deserialize; UpdateContract {
     desc: Option<String>,
     href: Option<String>,
     cta: Option<String>,
-    calls: Option<Vec<Call>>
+    calls: Option<Vec<Call>>,
+    block_sampling: Option<BlockSampling>,
 });
 
 #[route(post, "/admin/tasks/contract/update", auth_middleware)]
@@ -58,9 +61,19 @@ pub async fn handler(
     }
 
     if let Some(calls) = &body.calls {
+        if let Err(e) = calls.iter().try_for_each(validate_call) {
+            return get_error(format!("Invalid call: {}", e));
+        }
         update_doc.insert("calls", to_bson(calls).unwrap());
     }
 
+    if let Some(block_sampling) = &body.block_sampling {
+        if let Err(e) = validate_block_sampling(block_sampling) {
+            return get_error(format!("Invalid block sampling: {}", e));
+        }
+        update_doc.insert("block_sampling", to_bson(block_sampling).unwrap());
+    }
+
     // update quest query
     let update = doc! {
         "$set": update_doc
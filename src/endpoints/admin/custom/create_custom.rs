@@ -54,11 +54,16 @@ pub async fn handler(
         verify_endpoint_type: "default".to_string(),
         task_type: Some("custom".to_string()),
         discord_guild_id: None,
+        discord_role_ids: None,
+        telegram_chat_id: None,
         quiz_name: None,
         contracts: None,
         api_url: None,
         regex: None,
         calls: None,
+        rule: None,
+        block_sampling: None,
+        twitter_action: None,
     };
 
     // insert document to boost collection
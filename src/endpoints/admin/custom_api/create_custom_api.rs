@@ -57,10 +57,15 @@ pub async fn handler(
         calls: None,
         task_type: Some("custom_api".to_string()),
         discord_guild_id: None,
+        discord_role_ids: None,
+        telegram_chat_id: None,
         quiz_name: None,
         contracts: None,
         api_url: Some(body.api_url.clone()),
         regex: Some(body.regex.clone()),
+        rule: None,
+        block_sampling: None,
+        twitter_action: None,
     };
 
     // insert document to boost collection
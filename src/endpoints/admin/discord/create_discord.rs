@@ -20,6 +20,8 @@ pub_struct!(Deserialize; CreateCustom {
     desc: String,
     invite_link: String,
     guild_id: String,
+    // the member must have any of these roles, not all of them
+    role_ids: Option<Vec<String>>,
 });
 
 #[route(post, "/admin/tasks/discord/create", auth_middleware)]
@@ -53,12 +55,20 @@ pub async fn handler(
         verify_endpoint_type: "oauth_discord".to_string(),
         task_type: Some("discord".to_string()),
         discord_guild_id: Some(body.guild_id.clone()),
+        discord_role_ids: body
+            .role_ids
+            .clone()
+            .filter(|role_ids| !role_ids.is_empty()),
+        telegram_chat_id: None,
         quiz_name: None,
         verify_redirect: None,
         contracts: None,
         api_url: None,
         regex: None,
         calls: None,
+        rule: None,
+        block_sampling: None,
+        twitter_action: None,
     };
 
     // insert document to boost collection
@@ -19,6 +19,8 @@ pub_struct!(Deserialize; CreateCustom {
     desc: Option<String>,
     invite_link: Option<String>,
     guild_id: Option<String>,
+    // the member must have any of these roles, an empty list removing the requirement
+    role_ids: Option<Vec<String>>,
 });
 
 #[route(post, "/admin/tasks/discord/update", auth_middleware)]
@@ -53,6 +55,9 @@ pub async fn handler(
     if let Some(guild_id) = &body.guild_id {
         update_doc.insert("discord_guild_id", guild_id);
     }
+    if let Some(role_ids) = &body.role_ids {
+        update_doc.insert("discord_role_ids", role_ids);
+    }
 
     // update quest query
     let update = doc! {
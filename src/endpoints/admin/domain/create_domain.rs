@@ -50,12 +50,17 @@ pub async fn handler(
         task_type: Some("domain".to_string()),
         cta: "Register a domain".to_string(),
         discord_guild_id: None,
+        discord_role_ids: None,
+        telegram_chat_id: None,
         quiz_name: None,
         verify_redirect: None,
         contracts: None,
         api_url: None,
         regex: None,
         calls: None,
+        rule: None,
+        block_sampling: None,
+        twitter_action: None,
     };
 
     // insert document to boost collection
@@ -1,4 +1,7 @@
+pub mod achievement_categories;
+pub mod achievements;
 pub mod balance;
+pub mod buildings;
 pub mod contract;
 pub mod custom;
 pub mod custom_api;
@@ -10,5 +13,9 @@ pub mod nft_uri;
 pub mod quest;
 pub mod quest_boost;
 pub mod quiz;
+pub mod rule;
+pub mod season;
+pub mod telegram;
 pub mod twitter;
 pub mod user;
+pub mod xp;
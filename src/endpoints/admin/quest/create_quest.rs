@@ -1,6 +1,6 @@
 use crate::middleware::auth::auth_middleware;
 use crate::models::{QuestInsertDocument, QuestTaskDocument};
-use crate::utils::get_next_task_id;
+use crate::utils::{get_next_task_id, validate_return_url};
 use crate::{models::AppState, utils::get_error};
 use axum::{
     extract::{Extension, State},
@@ -26,6 +26,8 @@ pub_struct!(Deserialize; CreateQuestQuery {
     img_card: String,
     title_card: String,
     issuer: Option<String>,
+    snapshot_block: Option<i64>,
+    return_url: Option<String>,
 });
 
 #[route(post, "/admin/quest/create", auth_middleware)]
@@ -37,6 +39,12 @@ pub async fn handler(
     let collection = state.db.collection::<QuestInsertDocument>("quests");
     let insert_collection = state.db.collection::<QuestTaskDocument>("tasks");
 
+    if let Some(return_url) = &body.return_url {
+        if let Err(e) = validate_return_url(return_url) {
+            return get_error(e);
+        }
+    }
+
     let state_last_id = state.last_task_id.lock().await;
 
     let next_id = get_next_task_id(&insert_collection, state_last_id.clone()).await;
@@ -76,6 +84,14 @@ pub async fn handler(
         None => new_document.insert("expiry", None::<String>),
     };
 
+    if let Some(return_url) = &body.return_url {
+        new_document.insert("return_url", return_url);
+    }
+
+    if let Some(snapshot_block) = &body.snapshot_block {
+        new_document.insert("snapshot_block", snapshot_block);
+    }
+
     match issuer == "Starknet ID" {
         true => new_document.insert("experience", 50),
         false => new_document.insert("experience", 10),
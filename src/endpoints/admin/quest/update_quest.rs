@@ -1,5 +1,6 @@
 use crate::middleware::auth::auth_middleware;
 use crate::models::QuestDocument;
+use crate::utils::validate_return_url;
 use crate::{models::AppState, utils::get_error};
 use axum::{
     extract::{Extension, State},
@@ -26,6 +27,8 @@ pub_struct!(Deserialize; UpdateQuestQuery {
     img_card: Option<String>,
     title_card: Option<String>,
     issuer: Option<String>,
+    snapshot_block: Option<i64>,
+    return_url: Option<String>,
 });
 
 #[route(post, "/admin/quest/update", auth_middleware)]
@@ -95,6 +98,16 @@ pub async fn handler(
         update_doc.insert("title_card", title_card);
     }
 
+    if let Some(snapshot_block) = &body.snapshot_block {
+        update_doc.insert("snapshot_block", snapshot_block);
+    }
+    if let Some(return_url) = &body.return_url {
+        if let Err(e) = validate_return_url(return_url) {
+            return get_error(e);
+        }
+        update_doc.insert("return_url", return_url);
+    }
+
     // update quest query
     let update = doc! {
         "$set": update_doc
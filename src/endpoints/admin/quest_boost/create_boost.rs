@@ -1,6 +1,11 @@
+use crate::common::boost_distribution::{resolve_boost_rewards, validate_boost_distribution};
 use crate::middleware::auth::auth_middleware;
-use crate::models::{BoostTable, QuestDocument, QuestTaskDocument};
+use crate::models::{
+    BoostDistribution, BoostRewardQuery, BoostTable, BoostTier, BoostWeighting, QuestDocument,
+    QuestTaskDocument,
+};
 use crate::utils::get_next_task_id;
+use crate::utils::to_hex;
 use crate::utils::verify_quest_auth;
 use crate::{models::AppState, utils::get_error};
 use axum::{
@@ -12,19 +17,24 @@ use axum_auto_routes::route;
 use mongodb::bson::doc;
 use serde::Deserialize;
 use serde_json::json;
+use starknet::core::types::FieldElement;
 use std::sync::Arc;
 
 #[derive(Deserialize)]
 pub struct CreateBoostQuery {
-    amount: i32,
+    amount: String,
     token: String,
     num_of_winners: i64,
-    token_decimals: i64,
+    token_decimals: Option<i64>,
+    rewards: Option<Vec<BoostRewardQuery>>,
     name: String,
     quest_id: i32,
     hidden: bool,
     expiry: i64,
     img_url: String,
+    weighting: Option<BoostWeighting>,
+    distribution: Option<BoostDistribution>,
+    tiers: Option<Vec<BoostTier>>,
 }
 
 #[route(post, "/admin/quest_boost/create_boost", auth_middleware)]
@@ -42,6 +52,27 @@ pub async fn handler(
         return get_error("Error creating boost".to_string());
     };
 
+    // the main token may be outside of the registry as long as its decimals are given
+    let (token, token_decimals) = match (state.conf.tokens.find(&body.token), body.token_decimals) {
+        (Some(token), _) => (to_hex(token.contract), token.decimals),
+        (None, Some(token_decimals)) if FieldElement::from_hex_be(&body.token).is_ok() => {
+            (body.token.clone(), token_decimals)
+        }
+        _ => return get_error(format!("Unknown token {}", body.token)),
+    };
+    let rewards = match resolve_boost_rewards(&state, &body.rewards) {
+        Ok(rewards) => rewards,
+        Err(e) => return get_error(e),
+    };
+    if let Err(e) = validate_boost_distribution(
+        &body.distribution,
+        &body.tiers,
+        &body.amount,
+        token_decimals,
+    ) {
+        return get_error(e);
+    }
+
     let state_last_id = state.last_task_id.lock().await;
 
     let next_id = get_next_task_id(&insert_collection, state_last_id.clone()).await;
@@ -49,8 +80,8 @@ pub async fn handler(
     let new_document = BoostTable {
         name: body.name.clone(),
         amount: body.amount.clone(),
-        token_decimals: body.token_decimals.clone(),
-        token: body.token.clone(),
+        token_decimals,
+        token,
         expiry: body.expiry.clone(),
         num_of_winners: body.num_of_winners.clone(),
         quests: vec![body.quest_id.clone()],
@@ -58,6 +89,11 @@ pub async fn handler(
         hidden: body.hidden.clone(),
         img_url: body.img_url.clone(),
         winner: None,
+        rewards,
+        weighting: body.weighting.clone(),
+        distribution: body.distribution.clone(),
+        tiers: body.tiers.clone(),
+        raffle: None,
     };
 
     // insert document to boost collection
@@ -0,0 +1,111 @@
+use crate::common::boost_distribution::{get_claimable_amounts, is_claim_of_token};
+use crate::middleware::auth::auth_middleware;
+use crate::models::{BoostClaimDocument, BoostTable, QuestDocument};
+use crate::utils::verify_quest_auth;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use futures::TryStreamExt;
+use mongodb::bson::doc;
+use num_bigint::BigUint;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct GetClaimsQuery {
+    boost_id: i32,
+}
+
+#[route(get, "/admin/quest_boost/get_claims", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<GetClaimsQuery>,
+    Extension(sub): Extension<String>,
+) -> impl IntoResponse {
+    let collection = state.db.collection::<BoostTable>("boosts");
+    let quests_collection = state.db.collection::<QuestDocument>("quests");
+    let claims_collection = state.db.collection::<BoostClaimDocument>("boost_claims");
+
+    let boost = match collection.find_one(doc! {"id": query.boost_id}, None).await {
+        Ok(Some(boost)) => boost,
+        Ok(None) => return get_error("boost does not exist".to_string()),
+        Err(_) => return get_error("Error querying boost".to_string()),
+    };
+    if !verify_quest_auth(sub, &quests_collection, &(boost.quests[0] as i64)).await {
+        return get_error("Error querying claims".to_string());
+    }
+
+    // only claims whose cursor is still open are part of the canonical chain
+    let claims: Vec<BoostClaimDocument> = match claims_collection
+        .find(doc! {"id": boost.id, "_cursor.to": null}, None)
+        .await
+    {
+        Ok(cursor) => match cursor.try_collect().await {
+            Ok(claims) => claims,
+            Err(_) => return get_error("Error querying claims".to_string()),
+        },
+        Err(_) => return get_error("Error querying claims".to_string()),
+    };
+
+    let winners = boost.winner.clone().unwrap_or_default();
+    let (mut claimed, mut unclaimed) = (0, 0);
+    // amounts are kept per token as a boost can pay several of them, a winner being claimed once
+    // every token owed was claimed
+    let mut claimed_amounts: HashMap<String, BigUint> = HashMap::new();
+    let mut unclaimed_amounts: HashMap<String, BigUint> = HashMap::new();
+    for (rank, winner) in winners.iter().enumerate() {
+        let amounts = match get_claimable_amounts(&state, &boost, rank) {
+            Ok(amounts) => amounts,
+            Err(e) => return get_error(e),
+        };
+        let mut has_claimed_all = true;
+        for (reward, amount) in amounts {
+            let token_claims: Vec<&BoostClaimDocument> = claims
+                .iter()
+                .filter(|claim| {
+                    claim.winner == *winner && is_claim_of_token(claim, &boost, &reward.token)
+                })
+                .collect();
+            if token_claims.is_empty() {
+                has_claimed_all = false;
+                *unclaimed_amounts.entry(reward.token).or_default() += amount;
+                continue;
+            }
+            let claimed_amount = claimed_amounts.entry(reward.token).or_default();
+            for claim in token_claims {
+                *claimed_amount += BigUint::from_str(&claim.amount).unwrap_or_default();
+            }
+        }
+        if has_claimed_all {
+            claimed += 1;
+        } else {
+            unclaimed += 1;
+        }
+    }
+    let to_strings = |amounts: HashMap<String, BigUint>| -> HashMap<String, String> {
+        amounts
+            .into_iter()
+            .map(|(token, amount)| (token, amount.to_string()))
+            .collect()
+    };
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "boost_id": boost.id,
+            "winners": winners.len(),
+            "claimed": claimed,
+            "unclaimed": unclaimed,
+            "claimed_amounts": to_strings(claimed_amounts),
+            "unclaimed_amounts": to_strings(unclaimed_amounts),
+        })),
+    )
+        .into_response()
+}
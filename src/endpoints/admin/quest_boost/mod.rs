@@ -1,2 +1,3 @@
 pub mod create_boost;
+pub mod get_claims;
 pub mod update_boost;
@@ -1,6 +1,9 @@
+use crate::common::boost_distribution::{resolve_boost_rewards, validate_boost_distribution};
 use crate::middleware::auth::auth_middleware;
-use crate::models::{BoostTable, QuestDocument};
-use crate::utils::verify_quest_auth;
+use crate::models::{
+    BoostDistribution, BoostRewardQuery, BoostTable, BoostTier, BoostWeighting, QuestDocument,
+};
+use crate::utils::{to_hex, verify_quest_auth};
 use crate::{models::AppState, utils::get_error};
 use axum::{
     extract::{Extension, State},
@@ -8,7 +11,7 @@ use axum::{
     response::{IntoResponse, Json},
 };
 use axum_auto_routes::route;
-use mongodb::bson::{doc, Document};
+use mongodb::bson::{doc, to_bson, Document};
 use mongodb::options::FindOneAndUpdateOptions;
 use serde::Deserialize;
 use serde_json::json;
@@ -16,7 +19,7 @@ use std::sync::Arc;
 
 pub_struct!(Deserialize; UpdateBoostQuery {
     id: i32,
-    amount: Option<i32>,
+    amount: Option<String>,
     token: Option<String>,
     num_of_winners: Option<i64>,
     token_decimals: Option<i64>,
@@ -24,6 +27,10 @@ pub_struct!(Deserialize; UpdateBoostQuery {
     name: Option<String>,
     img_url: Option<String>,
     hidden: Option<bool>,
+    rewards: Option<Vec<BoostRewardQuery>>,
+    weighting: Option<BoostWeighting>,
+    distribution: Option<BoostDistribution>,
+    tiers: Option<Vec<BoostTier>>,
 });
 
 #[route(post, "/admin/quest_boost/update_boost", auth_middleware)]
@@ -43,13 +50,41 @@ pub async fn handler(
     if res.is_none() {
         return get_error("boost does not exist".to_string());
     }
-    let quest_id = res.as_ref().unwrap().quests[0];
+    let boost = res.as_ref().unwrap();
+    let quest_id = boost.quests[0];
     let res = verify_quest_auth(sub, &questcollection, &(quest_id as i64)).await;
 
     if !res {
         return get_error("Error updating boost".to_string());
     };
 
+    // tokens of the registry are stored by address with their registered decimals
+    let (token, token_decimals) = match (&body.token, body.token_decimals) {
+        (Some(token), token_decimals) => match state.conf.tokens.find(token) {
+            Some(registered) => (Some(to_hex(registered.contract)), Some(registered.decimals)),
+            None if token_decimals.is_some() => (Some(token.clone()), token_decimals),
+            None => return get_error(format!("Unknown token {}", token)),
+        },
+        (None, token_decimals) => (None, token_decimals),
+    };
+    let rewards = match resolve_boost_rewards(&state, &body.rewards) {
+        Ok(rewards) => rewards,
+        Err(e) => return get_error(e),
+    };
+
+    // the distribution is validated against the values it ends up with
+    let distribution = body.distribution.clone().or(boost.distribution.clone());
+    let tiers = body.tiers.clone().or(boost.tiers.clone());
+    let amount = body.amount.clone().unwrap_or(boost.amount.clone());
+    if let Err(e) = validate_boost_distribution(
+        &distribution,
+        &tiers,
+        &amount,
+        token_decimals.unwrap_or(boost.token_decimals),
+    ) {
+        return get_error(e);
+    }
+
     // filter to get existing boost
     let filter = doc! {
         "id": &body.id,
@@ -60,7 +95,7 @@ pub async fn handler(
     if let Some(amount) = &body.amount {
         update_doc.insert("amount", amount);
     }
-    if let Some(token) = &body.token {
+    if let Some(token) = &token {
         update_doc.insert("token", token);
     }
     if let Some(expiry) = &body.expiry {
@@ -69,9 +104,12 @@ pub async fn handler(
     if let Some(num_of_winners) = &body.num_of_winners {
         update_doc.insert("num_of_winners", num_of_winners);
     }
-    if let Some(token_decimals) = &body.token_decimals {
+    if let Some(token_decimals) = &token_decimals {
         update_doc.insert("token_decimals", token_decimals);
     }
+    if let Some(rewards) = &rewards {
+        update_doc.insert("rewards", to_bson(rewards).unwrap());
+    }
     if let Some(name) = &body.name {
         update_doc.insert("name", name);
     }
@@ -81,6 +119,15 @@ pub async fn handler(
     if let Some(hidden) = &body.hidden {
         update_doc.insert("hidden", hidden);
     }
+    if let Some(weighting) = &body.weighting {
+        update_doc.insert("weighting", to_bson(weighting).unwrap());
+    }
+    if let Some(distribution) = &body.distribution {
+        update_doc.insert("distribution", to_bson(distribution).unwrap());
+    }
+    if let Some(tiers) = &body.tiers {
+        update_doc.insert("tiers", to_bson(tiers).unwrap());
+    }
 
     // update boost
     let update = doc! {
@@ -88,11 +88,16 @@ pub async fn handler(
         quiz_name: Some(next_quiz_id.clone() as i64),
         task_type: Some("quiz".to_string()),
         discord_guild_id: None,
+        discord_role_ids: None,
+        telegram_chat_id: None,
         verify_redirect: None,
         contracts: None,
         api_url: None,
         regex: None,
         calls: None,
+        rule: None,
+        block_sampling: None,
+        twitter_action: None,
     };
 
     return match tasks_collection.insert_one(new_document, None).await {
@@ -0,0 +1,2 @@
+pub mod create_rule;
+pub mod update_rule;
@@ -0,0 +1,123 @@
+use crate::common::leaderboard::get_window_stages;
+use crate::middleware::auth::auth_middleware;
+use crate::models::{SeasonDocument, SeasonSnapshotDocument, SeasonStanding};
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, to_bson, Bson, Document};
+use mongodb::options::UpdateOptions;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+const DEFAULT_STANDINGS_SIZE: u32 = 100;
+
+pub_struct!(Deserialize; CloseSeasonQuery {
+    id: i32,
+    top: Option<u32>,
+});
+
+#[route(post, "/admin/season/close", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<CloseSeasonQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    let collection = state.db.collection::<SeasonDocument>("seasons");
+    let season = match collection.find_one(doc! { "id": body.id }, None).await {
+        Ok(Some(season)) => season,
+        Ok(None) => return get_error(format!("Season {} not found", body.id)),
+        Err(_) => return get_error("Error querying season".to_string()),
+    };
+    if season.closed {
+        return get_error(format!("Season {} is already closed", body.id));
+    }
+
+    // a season closed early ends now, otherwise at its planned end
+    let now = Utc::now().timestamp_millis();
+    let end = season.end.filter(|end| *end <= now).unwrap_or(now);
+
+    let mut pipeline = get_window_stages(season.start, Some(end));
+    pipeline.push(doc! { "$sort": { "experience": -1, "timestamp": 1, "_id": 1 } });
+    pipeline.push(doc! { "$limit": body.top.unwrap_or(DEFAULT_STANDINGS_SIZE) as i64 });
+    let rows: Vec<Document> = match state
+        .db
+        .collection::<Document>("leaderboard_daily")
+        .aggregate(pipeline, None)
+        .await
+    {
+        Ok(cursor) => match cursor.try_collect().await {
+            Ok(rows) => rows,
+            Err(_) => return get_error("Error computing standings".to_string()),
+        },
+        Err(_) => return get_error("Error computing standings".to_string()),
+    };
+    let standings: Vec<SeasonStanding> = rows
+        .iter()
+        .enumerate()
+        .filter_map(|(index, row)| {
+            let xp = match row.get("experience") {
+                Some(Bson::Int32(xp)) => *xp as i64,
+                Some(Bson::Int64(xp)) => *xp,
+                Some(Bson::Double(xp)) => *xp as i64,
+                _ => return None,
+            };
+            Some(SeasonStanding {
+                rank: index as u32 + 1,
+                address: row.get_str("_id").ok()?.to_string(),
+                xp,
+            })
+        })
+        .collect();
+
+    // the snapshot is only written once so that archived standings never change
+    let snapshot = SeasonSnapshotDocument {
+        season_id: season.id,
+        closed_at: end,
+        standings,
+    };
+    let snapshot = match to_bson(&snapshot) {
+        Ok(snapshot) => snapshot,
+        Err(_) => return get_error("Error serializing standings".to_string()),
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    if state
+        .db
+        .collection::<SeasonSnapshotDocument>("season_snapshots")
+        .update_one(
+            doc! { "season_id": season.id },
+            doc! { "$setOnInsert": snapshot },
+            options,
+        )
+        .await
+        .is_err()
+    {
+        return get_error("Error saving standings".to_string());
+    }
+
+    match collection
+        .update_one(
+            doc! { "id": season.id },
+            doc! { "$set": { "closed": true, "end": end } },
+            None,
+        )
+        .await
+    {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "Season closed successfully", "end": end})),
+        )
+            .into_response(),
+        Err(_) => get_error("Error closing season".to_string()),
+    }
+}
@@ -0,0 +1,2 @@
+pub mod close_season;
+pub mod open_season;
@@ -0,0 +1,67 @@
+use crate::middleware::auth::auth_middleware;
+use crate::models::SeasonDocument;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::doc;
+use mongodb::options::FindOneOptions;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+
+pub_struct!(Deserialize; OpenSeasonQuery {
+    name: String,
+    start: i64,
+    end: Option<i64>,
+});
+
+#[route(post, "/admin/season/open", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<OpenSeasonQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    if let Some(end) = body.end {
+        if end <= body.start {
+            return get_error("Season end must be after its start".to_string());
+        }
+    }
+
+    let collection = state.db.collection::<SeasonDocument>("seasons");
+
+    // only one season can be running at a time
+    match collection.find_one(doc! { "closed": false }, None).await {
+        Ok(Some(season)) => {
+            return get_error(format!("Season {} is still open", season.id));
+        }
+        Ok(None) => {}
+        Err(_) => return get_error("Error querying seasons".to_string()),
+    }
+
+    let options = FindOneOptions::builder().sort(doc! {"id": -1}).build();
+    let id = match collection.find_one(doc! {}, options).await {
+        Ok(Some(season)) => season.id + 1,
+        Ok(None) => 1,
+        Err(_) => return get_error("Error querying seasons".to_string()),
+    };
+
+    let new_document = SeasonDocument {
+        id,
+        name: body.name,
+        start: body.start,
+        end: body.end,
+        closed: false,
+    };
+    match collection.insert_one(new_document, None).await {
+        Ok(_) => (StatusCode::OK, Json(json!({"id": id}))).into_response(),
+        Err(_) => get_error("Error opening season".to_string()),
+    }
+}
@@ -0,0 +1,2 @@
+pub mod create_telegram;
+pub mod update_telegram;
@@ -1,5 +1,5 @@
 use crate::middleware::auth::auth_middleware;
-use crate::models::{QuestDocument, QuestTaskDocument};
+use crate::models::{QuestDocument, QuestTaskDocument, TwitterAction};
 use crate::utils::get_next_task_id;
 use crate::utils::verify_quest_auth;
 use crate::{models::AppState, utils::get_error};
@@ -56,11 +56,18 @@ pub async fn handler(
         task_type: Some("twitter_fw".to_string()),
         cta: "Follow".to_string(),
         discord_guild_id: None,
+        discord_role_ids: None,
+        telegram_chat_id: None,
         quiz_name: None,
         contracts: None,
         api_url: None,
         regex: None,
         calls: None,
+        rule: None,
+        block_sampling: None,
+        twitter_action: Some(TwitterAction::Follow {
+            username: body.username.clone(),
+        }),
     };
 
     // insert document to boost collection
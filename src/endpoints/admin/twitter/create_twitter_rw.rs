@@ -1,3 +1,4 @@
+use crate::common::twitter::{get_tweet_action, get_tweet_cta};
 use crate::middleware::auth::auth_middleware;
 use crate::models::{QuestDocument, QuestTaskDocument};
 use crate::utils::get_next_task_id;
@@ -19,6 +20,8 @@ pub_struct!(Deserialize; CreateTwitterRw {
     desc: String,
     post_link: String,
     quest_id: i64,
+    // retweet, like or quote, defaults to retweet
+    action: Option<String>,
 });
 
 #[route(post, "/admin/tasks/twitter_rw/create", auth_middleware)]
@@ -36,6 +39,12 @@ pub async fn handler(
         return get_error("Error creating task".to_string());
     };
 
+    let action = body.action.as_deref().unwrap_or("retweet");
+    let twitter_action = match get_tweet_action(action, &body.post_link) {
+        Ok(twitter_action) => twitter_action,
+        Err(e) => return get_error(e),
+    };
+
     let state_last_id = state.last_task_id.lock().await;
 
     let next_id = get_next_task_id(&collection, state_last_id.clone()).await;
@@ -51,13 +60,18 @@ pub async fn handler(
         verify_endpoint: "quests/verify_twitter_rw".to_string(),
         verify_endpoint_type: "default".to_string(),
         task_type: Some("twitter_rw".to_string()),
-        cta: "Retweet".to_string(),
+        cta: get_tweet_cta(&twitter_action),
         discord_guild_id: None,
+        discord_role_ids: None,
+        telegram_chat_id: None,
         quiz_name: None,
         contracts: None,
         api_url: None,
         regex: None,
         calls: None,
+        rule: None,
+        block_sampling: None,
+        twitter_action: Some(twitter_action),
     };
 
     // insert document to boost collection
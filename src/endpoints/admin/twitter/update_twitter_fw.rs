@@ -1,5 +1,5 @@
 use crate::middleware::auth::auth_middleware;
-use crate::models::QuestTaskDocument;
+use crate::models::{QuestTaskDocument, TwitterAction};
 use crate::utils::verify_task_auth;
 use crate::{models::AppState, utils::get_error};
 use axum::{
@@ -8,7 +8,7 @@ use axum::{
     response::{IntoResponse, Json},
 };
 use axum_auto_routes::route;
-use mongodb::bson::{doc, Document};
+use mongodb::bson::{doc, to_bson, Document};
 use mongodb::options::FindOneAndUpdateOptions;
 use serde::Deserialize;
 use serde_json::json;
@@ -59,6 +59,10 @@ pub async fn handler(
             "https://twitter.com/intent/user?screen_name=".to_string() + username,
         );
         update_doc.insert("href", "https://twitter.com/".to_string() + username);
+        let twitter_action = TwitterAction::Follow {
+            username: username.clone(),
+        };
+        update_doc.insert("twitter_action", to_bson(&twitter_action).unwrap());
     }
 
     // update boost
@@ -1,5 +1,6 @@
+use crate::common::twitter::{get_task_action, get_tweet_action, get_tweet_cta};
 use crate::middleware::auth::auth_middleware;
-use crate::models::QuestTaskDocument;
+use crate::models::{QuestTaskDocument, TwitterAction};
 use crate::utils::verify_task_auth;
 use crate::{models::AppState, utils::get_error};
 use axum::{
@@ -8,7 +9,7 @@ use axum::{
     response::{IntoResponse, Json},
 };
 use axum_auto_routes::route;
-use mongodb::bson::{doc, Document};
+use mongodb::bson::{doc, to_bson, Document};
 use mongodb::options::FindOneAndUpdateOptions;
 use serde::Deserialize;
 use serde_json::json;
@@ -18,6 +19,8 @@ pub_struct!(Deserialize; UpdateTwitterRw {
     name: Option<String>,
     desc: Option<String>,
     post_link: Option<String>,
+    // retweet, like or quote
+    action: Option<String>,
     id: i32,
 });
 
@@ -41,9 +44,9 @@ pub async fn handler(
     let existing_task = &collection.find_one(filter.clone(), None).await.unwrap();
 
     // create a boost if it does not exist
-    if existing_task.is_none() {
+    let Some(existing_task) = existing_task else {
         return get_error("Task does not exist".to_string());
-    }
+    };
 
     let mut update_doc = Document::new();
 
@@ -57,6 +60,22 @@ pub async fn handler(
         update_doc.insert("verify_redirect", &post_link);
         update_doc.insert("href", &post_link);
     }
+    if body.post_link.is_some() || body.action.is_some() {
+        // the action and post the task checks default to its current ones
+        let current_action = match get_task_action(existing_task) {
+            Some(TwitterAction::Like { .. }) => "like",
+            Some(TwitterAction::Quote { .. }) => "quote",
+            _ => "retweet",
+        };
+        let action = body.action.as_deref().unwrap_or(current_action);
+        let post_link = body.post_link.as_ref().unwrap_or(&existing_task.href);
+        let twitter_action = match get_tweet_action(action, post_link) {
+            Ok(twitter_action) => twitter_action,
+            Err(e) => return get_error(e),
+        };
+        update_doc.insert("cta", get_tweet_cta(&twitter_action));
+        update_doc.insert("twitter_action", to_bson(&twitter_action).unwrap());
+    }
 
     // update boost
     let update = doc! {
@@ -0,0 +1,43 @@
+use crate::middleware::auth::auth_middleware;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
+use serde::Deserialize;
+use starknet::core::types::FieldElement;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct GetLedgerQuery {
+    addr: FieldElement,
+}
+
+#[route(get, "/admin/xp/ledger", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<GetLedgerQuery>,
+    Extension(sub): Extension<String>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    let collection = state.db.collection::<Document>("user_exp");
+    let options = FindOptions::builder().sort(doc! { "_id": 1 }).build();
+    match collection
+        .find(doc! { "address": query.addr.to_string() }, options)
+        .await
+    {
+        Ok(cursor) => match cursor.try_collect::<Vec<Document>>().await {
+            Ok(entries) => (StatusCode::OK, Json(entries)).into_response(),
+            Err(_) => get_error("Error querying ledger".to_string()),
+        },
+        Err(_) => get_error("Error querying ledger".to_string()),
+    }
+}
@@ -0,0 +1,47 @@
+use crate::common::xp_ledger::credit_experience;
+use crate::middleware::auth::auth_middleware;
+use crate::models::XpSource;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use serde::Deserialize;
+use serde_json::json;
+use starknet::core::types::FieldElement;
+use std::sync::Arc;
+
+pub_struct!(Deserialize; GrantXpQuery {
+    addr: FieldElement,
+    experience: i64,
+    reason: String,
+});
+
+#[route(post, "/admin/xp/grant", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<GrantXpQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+    if body.experience <= 0 {
+        return get_error("Experience must be positive".to_string());
+    }
+
+    let source = XpSource::Manual {
+        admin: sub,
+        reason: body.reason,
+    };
+    match credit_experience(&state.db, body.addr.to_string(), body.experience, source).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({"message": "Experience granted successfully"})),
+        )
+            .into_response(),
+        Err(_) => get_error("Error granting experience".to_string()),
+    }
+}
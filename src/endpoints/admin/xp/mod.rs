@@ -0,0 +1,3 @@
+pub mod get_ledger;
+pub mod grant_xp;
+pub mod revoke_xp;
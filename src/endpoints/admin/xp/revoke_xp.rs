@@ -0,0 +1,55 @@
+use crate::common::xp_ledger::{reverse_address_experience, reverse_experience};
+use crate::middleware::auth::auth_middleware;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::oid::ObjectId;
+use serde::Deserialize;
+use serde_json::json;
+use starknet::core::types::FieldElement;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// either a single ledger entry or every entry of an address is revoked
+pub_struct!(Deserialize; RevokeXpQuery {
+    entry_id: Option<String>,
+    addr: Option<FieldElement>,
+    reason: String,
+});
+
+#[route(post, "/admin/xp/revoke", auth_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(sub): Extension<String>,
+    Json(body): Json<RevokeXpQuery>,
+) -> impl IntoResponse {
+    if sub != "super_user" {
+        return get_error("Operation not allowed with your account".to_string());
+    };
+
+    let result = match (&body.entry_id, body.addr) {
+        (Some(entry_id), None) => {
+            let Ok(entry_id) = ObjectId::from_str(entry_id) else {
+                return get_error("Invalid entry id".to_string());
+            };
+            match reverse_experience(&state.db, entry_id, &sub, &body.reason).await {
+                Ok(Some(experience)) => Ok(experience),
+                Ok(None) => return get_error("Entry not found or already revoked".to_string()),
+                Err(e) => Err(e),
+            }
+        }
+        (None, Some(addr)) => {
+            reverse_address_experience(&state.db, &addr.to_string(), &sub, &body.reason).await
+        }
+        _ => return get_error("Either entry_id or addr must be given".to_string()),
+    };
+
+    match result {
+        Ok(experience) => (StatusCode::OK, Json(json!({"revoked": experience}))).into_response(),
+        Err(e) => get_error(e),
+    }
+}
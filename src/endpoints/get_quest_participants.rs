@@ -1,3 +1,5 @@
+use crate::common::starknetid::get_profiles;
+use crate::models::StarknetIdProfile;
 use crate::{models::AppState, utils::get_error};
 use axum::{
     extract::{Query, State},
@@ -7,7 +9,7 @@ use axum::{
 
 use axum_auto_routes::route;
 use futures::StreamExt;
-use mongodb::bson::{doc, Document};
+use mongodb::bson::{doc, to_bson, Document};
 use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -106,5 +108,25 @@ pub async fn handler(
         }
     }
 
+    // first participants are also returned with their starknet id domain and profile picture
+    let addresses: Vec<String> = match res.get_array("firstParticipants") {
+        Ok(participants) => participants
+            .iter()
+            .filter_map(|addr| addr.as_str().map(|addr| addr.to_string()))
+            .collect(),
+        Err(_) => vec![],
+    };
+    let profiles = get_profiles(&state, &addresses).await;
+    let profiles: Vec<StarknetIdProfile> = addresses
+        .iter()
+        .filter_map(|addr| profiles.get(addr).cloned())
+        .collect();
+    match to_bson(&profiles) {
+        Ok(profiles) => {
+            res.insert("firstParticipantsProfiles", profiles);
+        }
+        Err(_) => return get_error("Error querying quest participants".to_string()),
+    }
+
     return (StatusCode::OK, Json(res)).into_response();
 }
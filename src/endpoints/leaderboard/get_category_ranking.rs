@@ -0,0 +1,36 @@
+use crate::{
+    common::leaderboard::get_category_source,
+    endpoints::leaderboard::get_ranking::get_paginated_ranking, models::AppState,
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use axum_auto_routes::route;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct GetCategoryRankingQuery {
+    name: String,
+    addr: String,
+    page_size: i64,
+    shift: i64,
+}
+
+// ranks users by the experience earned from the completed quests of a category
+#[route(get, "/leaderboard/category")]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<GetCategoryRankingQuery>,
+) -> impl IntoResponse {
+    let (collection, source_stages) = get_category_source(&state.db, &query.name);
+    get_paginated_ranking(
+        &collection,
+        source_stages,
+        query.addr,
+        query.page_size,
+        query.shift,
+    )
+    .await
+}
@@ -0,0 +1,36 @@
+use crate::{
+    common::leaderboard::get_quest_source,
+    endpoints::leaderboard::get_ranking::get_paginated_ranking, models::AppState,
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use axum_auto_routes::route;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct GetQuestRankingQuery {
+    quest_id: u32,
+    addr: String,
+    page_size: i64,
+    shift: i64,
+}
+
+// ranks the completers of a quest, first to finish its last task first
+#[route(get, "/leaderboard/quest")]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<GetQuestRankingQuery>,
+) -> impl IntoResponse {
+    let (collection, source_stages) = get_quest_source(&state.db, query.quest_id);
+    get_paginated_ranking(
+        &collection,
+        source_stages,
+        query.addr,
+        query.page_size,
+        query.shift,
+    )
+    .await
+}
@@ -40,8 +40,9 @@ use axum::{
 };
 use axum_auto_routes::route;
 
-use crate::utils::get_timestamp_from_days;
-use axum::http::{header, Response};
+use crate::common::leaderboard::{get_leaderboard_source, get_season_source};
+use axum::http::header;
+use axum::response::Response;
 use chrono::Utc;
 use futures::TryStreamExt;
 use mongodb::bson::{doc, Document};
@@ -52,17 +53,11 @@ use std::sync::Arc;
 
 pub async fn get_user_rank(
     collection: &Collection<Document>,
+    source_stages: &[Document],
     address: &String,
-    timestamp: &i64,
 ) -> Document {
-    let user_rank_pipeline = vec![
-        doc! {
-            "$match": doc! {
-                "timestamp": doc! {
-                    "$gte": timestamp,
-                }
-            }
-        },
+    let mut user_rank_pipeline = source_stages.to_vec();
+    user_rank_pipeline.extend(vec![
         doc! {
             "$sort": doc! {
                 "experience": -1,
@@ -131,7 +126,7 @@ pub async fn get_user_rank(
                 "rank": "$rank.rank"
             }
         },
-    ];
+    ]);
 
     // add allow disk use to view options
     let view_options = mongodb::options::AggregateOptions::builder()
@@ -232,7 +227,19 @@ pub struct GetCompletedQuestsQuery {
     */
     shift: i64,
 
+    #[serde(default)]
     duration: String,
+
+    /*
+    bounds in milliseconds of a custom duration, rounded to whole days
+    */
+    start: Option<i64>,
+    end: Option<i64>,
+
+    /*
+    id of a season to rank instead of a duration
+    */
+    season: Option<i32>,
 }
 
 #[route(get, "/leaderboard/get_ranking")]
@@ -240,25 +247,36 @@ pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<GetCompletedQuestsQuery>,
 ) -> impl IntoResponse {
-    // check value of duration and set time_gap accordingly using match and respective timestamp
-    let time_gap = match query.duration.as_str() {
-        "week" => get_timestamp_from_days(7),
-        "month" => get_timestamp_from_days(30),
-        "all" => 0,
-        _ => {
-            return get_error("Invalid duration".to_string());
-        }
+    // seasons and windowed durations are ranked from the daily xp buckets they cover
+    let source = match query.season {
+        Some(season) => get_season_source(&state.db, season).await,
+        None => get_leaderboard_source(&state.db, &query.duration, query.start, query.end),
+    };
+    let (users_collection, source_stages) = match source {
+        Ok(source) => source,
+        Err(e) => return get_error(e),
     };
-    // get collection
-    let users_collection = state.db.collection::<Document>("leaderboard_table");
 
-    // get params from query
-    let address = query.addr.to_string();
-    let page_size = query.page_size;
-    let shift = query.shift;
+    get_paginated_ranking(
+        &users_collection,
+        source_stages,
+        query.addr.to_string(),
+        query.page_size,
+        query.shift,
+    )
+    .await
+}
 
+// page of the ranking produced by the source stages, centred on the given address
+pub async fn get_paginated_ranking(
+    users_collection: &Collection<Document>,
+    source_stages: Vec<Document>,
+    address: String,
+    page_size: i64,
+    shift: i64,
+) -> Response {
     // get user rank and total users
-    let stats = get_user_rank(&users_collection, &address, &time_gap).await;
+    let stats = get_user_rank(users_collection, &source_stages, &address).await;
     let total_users = stats.get("total_users").unwrap().as_i32().unwrap() as i64;
     let user_rank = stats.get("user_rank").unwrap().as_i32().unwrap() as i64;
 
@@ -298,14 +316,8 @@ pub async fn handler(
         }
     }
 
-    let paginated_leaderboard_pipeline = [
-        doc! {
-            "$match": doc!{
-                "timestamp": doc!{
-                    "$gte": time_gap,
-                }
-            }
-        },
+    let mut paginated_leaderboard_pipeline = source_stages;
+    paginated_leaderboard_pipeline.extend([
         doc! {
             "$sort":doc! {
                 "experience":-1,
@@ -337,7 +349,7 @@ pub async fn handler(
                 }
             }
         },
-    ];
+    ]);
 
     match users_collection
         .aggregate(paginated_leaderboard_pipeline, None)
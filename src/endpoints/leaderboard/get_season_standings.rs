@@ -0,0 +1,37 @@
+use crate::{
+    models::{AppState, SeasonSnapshotDocument},
+    utils::get_error,
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::doc;
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct GetSeasonStandingsQuery {
+    season: i32,
+}
+
+// archived standings of a closed season
+#[route(get, "/leaderboard/season_standings")]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<GetSeasonStandingsQuery>,
+) -> impl IntoResponse {
+    let collection = state
+        .db
+        .collection::<SeasonSnapshotDocument>("season_snapshots");
+    match collection
+        .find_one(doc! { "season_id": query.season }, None)
+        .await
+    {
+        Ok(Some(snapshot)) => (StatusCode::OK, Json(snapshot)).into_response(),
+        Ok(None) => get_error(format!("No standings for season {}", query.season)),
+        Err(_) => get_error("Error querying standings".to_string()),
+    }
+}
@@ -14,12 +14,13 @@ use axum::{
 };
 use axum_auto_routes::route;
 
-use crate::utils::get_timestamp_from_days;
+use crate::common::leaderboard::{get_leaderboard_source, get_season_source};
+use crate::common::starknetid::get_profiles;
 use axum::http::header;
 use axum::response::Response;
 use chrono::Utc;
 use futures::TryStreamExt;
-use mongodb::bson::{doc, Document};
+use mongodb::bson::{doc, to_bson, Document};
 use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -31,7 +32,19 @@ pub struct GetLeaderboardInfoQuery {
     */
     addr: String,
 
+    #[serde(default)]
     duration: String,
+
+    /*
+    bounds in milliseconds of a custom duration, rounded to whole days
+    */
+    start: Option<i64>,
+    end: Option<i64>,
+
+    /*
+    id of a season to rank instead of a duration
+    */
+    season: Option<i32>,
 }
 
 #[route(get, "/leaderboard/get_static_info")]
@@ -40,19 +53,19 @@ pub async fn handler(
     Query(query): Query<GetLeaderboardInfoQuery>,
 ) -> impl IntoResponse {
     let addr: String = query.addr.to_string();
-    let collection = state.db.collection::<Document>("leaderboard_table");
 
-    // check value of duration and set time_gap accordingly using match and respective timestamp
-    let time_gap = match query.duration.as_str() {
-        "week" => get_timestamp_from_days(7),
-        "month" => get_timestamp_from_days(30),
-        "all" => 0,
-        _ => {
-            return get_error("Invalid duration".to_string());
-        }
+    // seasons and windowed durations are ranked from the daily xp buckets they cover
+    let source = match query.season {
+        Some(season) => get_season_source(&state.db, season).await,
+        None => get_leaderboard_source(&state.db, &query.duration, query.start, query.end),
+    };
+    let (collection, source_stages) = match source {
+        Ok(source) => source,
+        Err(e) => return get_error(e),
     };
 
-    let leaderboard_pipeline = vec![
+    let mut leaderboard_pipeline = source_stages;
+    leaderboard_pipeline.extend(vec![
         doc! {
             "$sort": doc! {
                 "experience": -1,
@@ -60,13 +73,6 @@ pub async fn handler(
                 "_id": 1
             }
         },
-        doc! {
-            "$match": doc! {
-                "timestamp": doc! {
-                    "$gte": time_gap,
-                }
-            }
-        },
         doc! {
             "$facet": doc! {
                 "best_users": [
@@ -149,11 +155,35 @@ pub async fn handler(
                 }
             }
         },
-    ];
+    ]);
 
     return match collection.aggregate(leaderboard_pipeline, None).await {
         Ok(mut cursor) => {
-            while let Some(result) = cursor.try_next().await.unwrap() {
+            while let Some(mut result) = cursor.try_next().await.unwrap() {
+                // best users are returned with their starknet id profile, shaped like the profiles
+                // of the first quest participants
+                if let Ok(best_users) = result.get_array_mut("best_users") {
+                    let addresses: Vec<String> = best_users
+                        .iter()
+                        .filter_map(|user| user.as_document()?.get_str("address").ok())
+                        .map(|addr| addr.to_string())
+                        .collect();
+                    let profiles = get_profiles(&state, &addresses).await;
+                    for user in best_users.iter_mut() {
+                        let Some(user) = user.as_document_mut() else {
+                            continue;
+                        };
+                        let profile = user
+                            .get_str("address")
+                            .ok()
+                            .and_then(|addr| profiles.get(addr))
+                            .cloned();
+                        if let Some(Ok(profile)) = profile.map(|profile| to_bson(&profile)) {
+                            user.insert("profile", profile);
+                        }
+                    }
+                }
+
                 // Set caching response
                 let expires = Utc::now() + chrono::Duration::minutes(5);
                 let caching_response = Response::builder()
@@ -1,2 +1,5 @@
+pub mod get_category_ranking;
+pub mod get_quest_ranking;
 pub mod get_ranking;
+pub mod get_season_standings;
 pub mod get_static_info;
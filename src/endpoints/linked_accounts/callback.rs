@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use crate::common::{
+    discord,
+    linked_accounts::{get_oauth_provider, link_account, take_authorization},
+    twitter,
+};
+use crate::{
+    models::AppState,
+    utils::{get_error_redirect, success_redirect},
+};
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+};
+use axum_auto_routes::route;
+use serde::Deserialize;
+use starknet::core::types::FieldElement;
+
+#[derive(Deserialize)]
+pub struct OAuthCallbackQuery {
+    code: Option<String>,
+    state: String,
+}
+
+#[route(get, "/linked_accounts/callback")]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<OAuthCallbackQuery>,
+) -> impl IntoResponse {
+    let app_link = &state.conf.variables.app_link;
+    let authorization = match take_authorization(&state, &query.state).await {
+        Ok(Some(authorization)) => authorization,
+        Ok(None) => {
+            return get_error_redirect(
+                format!("{}?linked=false", app_link),
+                "Your authorization expired, please try again".to_string(),
+            )
+        }
+        Err(e) => return get_error_redirect(format!("{}?linked=false", app_link), e),
+    };
+    let provider_name = authorization.get_str("provider").unwrap_or_default();
+
+    // users linking their account from a task are sent back to it
+    let task = match (
+        authorization.get_i64("quest_id"),
+        authorization.get_i64("task_id"),
+    ) {
+        (Ok(quest_id), Ok(task_id)) => Some((quest_id, task_id as u32)),
+        _ => None,
+    };
+    let (error_redirect_uri, redirect_uri) = match task {
+        Some((quest_id, task_id)) => (
+            format!(
+                "{}/quest/{}?task_id={}&res=false",
+                app_link, quest_id, task_id
+            ),
+            format!(
+                "{}/quest/{}?task_id={}&res=true",
+                app_link, quest_id, task_id
+            ),
+        ),
+        None => (
+            format!("{}?linked=false&provider={}", app_link, provider_name),
+            format!("{}?linked=true&provider={}", app_link, provider_name),
+        ),
+    };
+
+    let Some(provider) = get_oauth_provider(&state, provider_name) else {
+        return get_error_redirect(error_redirect_uri, "Unsupported provider".to_string());
+    };
+    // the code is missing when the user denied the authorization
+    let Some(code) = &query.code else {
+        return get_error_redirect(error_redirect_uri, "Authorization was denied".to_string());
+    };
+    let account = match link_account(&state, &provider, &authorization, code).await {
+        Ok(account) => account,
+        Err(e) => return get_error_redirect(error_redirect_uri, e),
+    };
+
+    if let Some((quest_id, task_id)) = task {
+        let Ok(addr) = FieldElement::from_dec_str(&account.address) else {
+            return get_error_redirect(error_redirect_uri, "Invalid address".to_string());
+        };
+        let result = match provider.name {
+            "twitter" => twitter::verify_task(&state, addr, quest_id, task_id).await,
+            "discord" => discord::verify_task(&state, addr, quest_id, task_id).await,
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            return get_error_redirect(error_redirect_uri, e);
+        }
+    }
+    success_redirect(redirect_uri)
+}
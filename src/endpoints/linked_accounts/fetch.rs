@@ -0,0 +1,45 @@
+use crate::middleware::session::session_middleware;
+use std::sync::Arc;
+
+use crate::common::linked_accounts::get_linked_accounts;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_auto_routes::route;
+use serde::Deserialize;
+use serde_json::json;
+use starknet::core::types::FieldElement;
+
+#[derive(Deserialize)]
+pub struct LinkedAccountsQuery {
+    addr: FieldElement,
+}
+
+#[route(get, "/linked_accounts", session_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LinkedAccountsQuery>,
+) -> impl IntoResponse {
+    match get_linked_accounts(&state, query.addr).await {
+        // tokens never leave the server
+        Ok(accounts) => {
+            let accounts: Vec<_> = accounts
+                .into_iter()
+                .map(|account| {
+                    json!({
+                        "provider": account.provider,
+                        "account_id": account.account_id,
+                        "username": account.username,
+                        "linked_at": account.linked_at,
+                    })
+                })
+                .collect();
+            (StatusCode::OK, Json(json!({ "accounts": accounts }))).into_response()
+        }
+        Err(e) => get_error(e),
+    }
+}
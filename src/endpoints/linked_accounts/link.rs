@@ -0,0 +1,39 @@
+use crate::middleware::session::session_middleware;
+use std::sync::Arc;
+
+use crate::common::linked_accounts::{create_authorization, get_oauth_provider};
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_auto_routes::route;
+use serde::Deserialize;
+use serde_json::json;
+use starknet::core::types::FieldElement;
+
+#[derive(Deserialize)]
+pub struct LinkQuery {
+    addr: FieldElement,
+    provider: String,
+    quest_id: Option<i64>,
+    task_id: Option<u32>,
+}
+
+// returns the authorization url linking an account of the provider to the address, the task
+// given being verified once the account is linked
+#[route(get, "/linked_accounts/link", session_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LinkQuery>,
+) -> impl IntoResponse {
+    let Some(provider) = get_oauth_provider(&state, &query.provider) else {
+        return get_error(format!("Unsupported provider {}", query.provider));
+    };
+    match create_authorization(&state, &provider, query.addr, query.quest_id, query.task_id).await {
+        Ok(url) => (StatusCode::OK, Json(json!({ "url": url }))).into_response(),
+        Err(e) => get_error(e),
+    }
+}
@@ -0,0 +1,5 @@
+pub mod callback;
+pub mod fetch;
+pub mod link;
+pub mod telegram;
+pub mod unlink;
@@ -0,0 +1,37 @@
+use crate::middleware::session::session_middleware;
+use std::sync::Arc;
+
+use crate::common::telegram::link_account;
+use crate::{
+    models::{AppState, TelegramAuthData, WalletSession},
+    utils::get_error,
+};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_auto_routes::route;
+use serde_json::json;
+
+// links the telegram account of a Telegram Login Widget authorization to the session address
+#[route(post, "/linked_accounts/telegram", session_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(session): Extension<WalletSession>,
+    Json(body): Json<TelegramAuthData>,
+) -> impl IntoResponse {
+    match link_account(&state, session.addr, &body).await {
+        Ok(account) => (
+            StatusCode::OK,
+            Json(json!({
+                "provider": account.provider,
+                "account_id": account.account_id,
+                "username": account.username,
+            })),
+        )
+            .into_response(),
+        Err(e) => get_error(e),
+    }
+}
@@ -0,0 +1,37 @@
+use crate::middleware::session::session_middleware;
+use std::sync::Arc;
+
+use crate::common::linked_accounts::{unlink_account, PROVIDERS};
+use crate::{
+    models::{AppState, WalletSession},
+    utils::get_error,
+};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_auto_routes::route;
+use serde::Deserialize;
+use serde_json::json;
+
+pub_struct!(Deserialize; UnlinkAccount {
+    provider: String,
+});
+
+#[route(post, "/linked_accounts/unlink", session_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Extension(session): Extension<WalletSession>,
+    Json(body): Json<UnlinkAccount>,
+) -> impl IntoResponse {
+    if !PROVIDERS.contains(&body.provider.as_str()) {
+        return get_error(format!("Unsupported provider {}", body.provider));
+    }
+    match unlink_account(&state, session.addr, &body.provider).await {
+        Ok(true) => (StatusCode::OK, Json(json!({ "res": true }))).into_response(),
+        Ok(false) => get_error("No account linked for this provider".to_string()),
+        Err(e) => get_error(e),
+    }
+}
@@ -14,7 +14,9 @@ pub mod get_tasks;
 pub mod get_trending_quests;
 pub mod has_completed_quest;
 pub mod leaderboard;
+pub mod linked_accounts;
 pub mod quest_boost;
 pub mod quests;
+pub mod session;
 pub mod unique_page_visit;
 pub mod defi;
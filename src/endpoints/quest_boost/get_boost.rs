@@ -31,7 +31,9 @@ pub async fn handler(
         },
         doc! {
             "$project":{
-            "_id":0
+            "_id":0,
+            "raffle.participants":0,
+            "raffle.weights":0
             }
         },
     ];
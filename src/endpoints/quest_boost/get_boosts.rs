@@ -8,12 +8,17 @@ use axum::{
 use axum_auto_routes::route;
 use futures::StreamExt;
 use mongodb::bson::doc;
+use mongodb::options::FindOptions;
 use std::sync::Arc;
 
 #[route(get, "/boost/get_boosts")]
 pub async fn handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     let collection = state.db.collection::<BoostTable>("boosts");
-    let mut boosts = match collection.find(doc! {"hidden":false}, None).await {
+    // participant snapshots are only served by /boost/raffle_proof
+    let options = FindOptions::builder()
+        .projection(doc! {"raffle": 0})
+        .build();
+    let mut boosts = match collection.find(doc! {"hidden":false}, options).await {
         Ok(cursor) => cursor,
         Err(_) => return get_error("Error querying boosts".to_string()),
     };
@@ -1,3 +1,5 @@
+use crate::common::boost_distribution::get_claimable_amounts;
+use crate::models::BoostTable;
 use crate::{models::AppState, utils::get_error};
 use axum::{
     extract::{Query, State},
@@ -7,8 +9,8 @@ use axum::{
 use axum_auto_routes::route;
 use std::str::FromStr;
 
-use crate::utils::to_hex;
-use mongodb::bson::{doc, Bson, Document};
+use crate::utils::{to_hex, to_u256};
+use mongodb::bson::doc;
 use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -29,56 +31,70 @@ pub async fn handler(
 ) -> impl IntoResponse {
     let address = to_hex(query.addr);
     let boost_id = query.boost_id;
-    let collection = state.db.collection::<Document>("boosts");
-    let res = collection
-        .find_one(doc! {"id":boost_id}, None)
-        .await
-        .unwrap();
-
-    // if no boost found with the requested id
-    if res.is_none() {
-        return get_error(format!("Boost with id {} not found", boost_id));
-    }
-
-    let boost: Document = res.unwrap();
-    let num_of_winners = boost.get("num_of_winners").unwrap().as_i32().unwrap();
-    let decimals = boost.get("token_decimals").unwrap().as_i32().unwrap();
-    let amount: u128 =
-        boost.get("amount").unwrap().as_i32().unwrap() as u128 * 10u128.pow(decimals as u32);
-    let modified_amount = amount / num_of_winners as u128;
-    let token = boost.get("token").unwrap().as_str().unwrap();
-
-    let winner_list = boost.get("winner").unwrap().as_array().unwrap();
-    let bson_value: Bson = Bson::String(address.clone());
+    let collection = state.db.collection::<BoostTable>("boosts");
+    let boost = match collection.find_one(doc! {"id":boost_id}, None).await {
+        Ok(Some(boost)) => boost,
+        // if no boost found with the requested id
+        Ok(None) => return get_error(format!("Boost with id {} not found", boost_id)),
+        Err(_) => return get_error("Error querying boost".to_string()),
+    };
 
     // if the user is not in the winner list
-    if !winner_list.contains(&bson_value) {
+    let winner_list = boost.winner.clone().unwrap_or_default();
+    let Some(rank) = winner_list.iter().position(|winner| *winner == address) else {
         return get_error(format!(
             "User {} is not in the winner list",
             address.clone()
         ));
-    }
+    };
+
+    // the amounts owed depend on the boost distribution and on the winner rank
+    let amounts = match get_claimable_amounts(&state, &boost, rank) {
+        Ok(amounts) => amounts,
+        Err(e) => return get_error(e),
+    };
 
-    let hashed = pedersen_hash(
-        &FieldElement::from(boost_id),
-        &pedersen_hash(
-            &FieldElement::from(modified_amount),
+    // one signature is generated per token, amounts being signed as u256 (low, high)
+    let mut rewards = Vec::new();
+    for (reward, amount) in amounts {
+        let (amount_low, amount_high) = match to_u256(&amount) {
+            Ok(amount) => amount,
+            Err(e) => return get_error(e),
+        };
+        let Ok(token) = FieldElement::from_str(&reward.token) else {
+            return get_error(format!("Invalid token address {}", reward.token));
+        };
+        let hashed = pedersen_hash(
+            &FieldElement::from(boost_id),
             &pedersen_hash(
-                &FieldElement::from(0 as u32),
+                &amount_low,
                 &pedersen_hash(
-                    &FieldElement::from_str(token).unwrap(),
-                    &FieldElement::from_str(&*address).unwrap(),
+                    &amount_high,
+                    &pedersen_hash(&token, &FieldElement::from_str(&*address).unwrap()),
                 ),
             ),
-        ),
-    );
+        );
 
-    match ecdsa_sign(&state.conf.quest_boost.private_key, &hashed) {
-        Ok(signature) => (
-            StatusCode::OK,
-            Json(json!({"address": address, "r": signature.r, "s": signature.s})),
-        )
-            .into_response(),
-        Err(e) => get_error(format!("Error while generating signature: {}", e)),
+        match ecdsa_sign(&state.conf.quest_boost.private_key, &hashed) {
+            Ok(signature) => rewards.push(json!({
+                "token": reward.token,
+                "amount": amount.to_string(),
+                "r": signature.r,
+                "s": signature.s,
+            })),
+            Err(e) => return get_error(format!("Error while generating signature: {}", e)),
+        }
     }
+
+    // the main token signature stays at the top level for existing clients
+    (
+        StatusCode::OK,
+        Json(json!({
+            "address": address,
+            "r": rewards[0]["r"],
+            "s": rewards[0]["s"],
+            "rewards": rewards,
+        })),
+    )
+        .into_response()
 }
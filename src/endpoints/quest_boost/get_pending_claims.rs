@@ -1,3 +1,4 @@
+use crate::common::boost_distribution::{has_multi_token_claims, is_same_token};
 use crate::utils::to_hex;
 use crate::{
     models::{AppState, QuestDocument},
@@ -10,7 +11,7 @@ use axum::{
 };
 use axum_auto_routes::route;
 use futures::StreamExt;
-use mongodb::bson::doc;
+use mongodb::bson::{doc, Bson, Document};
 use serde::Deserialize;
 use starknet::core::types::FieldElement;
 use std::sync::Arc;
@@ -20,6 +21,37 @@ pub struct GetQuestsQuery {
     addr: FieldElement,
 }
 
+// a boost stays pending until every token it pays was claimed, claims without a token being
+// claims of its main token
+fn has_pending_tokens(boost: &Document, claims: Option<Bson>, multi_token_claims: bool) -> bool {
+    let Ok(main_token) = boost.get_str("token") else {
+        return false;
+    };
+    let mut tokens = vec![main_token];
+    if multi_token_claims {
+        if let Ok(rewards) = boost.get_array("rewards") {
+            tokens.extend(
+                rewards
+                    .iter()
+                    .filter_map(|reward| reward.as_document()?.get_str("token").ok()),
+            );
+        }
+    }
+    let claimed_tokens: Vec<&str> = match &claims {
+        Some(Bson::Array(claims)) => claims
+            .iter()
+            .filter_map(Bson::as_document)
+            .map(|claim| claim.get_str("token").unwrap_or(main_token))
+            .collect(),
+        _ => vec![],
+    };
+    tokens.iter().any(|token| {
+        !claimed_tokens
+            .iter()
+            .any(|claimed| is_same_token(claimed, token))
+    })
+}
+
 #[route(get, "/boost/get_pending_claims")]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
@@ -77,34 +109,25 @@ pub async fn handler(
                 "as": "boost_claims"
             }
         },
-        doc! {
-            "$match": doc! {
-                "$expr": doc! {
-                    "$eq": [
-                        doc! {
-                            "$size": "$boost_claims"
-                        },
-                        0
-                    ]
-                }
-            }
-        },
         doc! {
             "$project": doc! {
                 "_id": 0,
-                "boost_claims": 0,
                 "hidden": 0
             }
         },
     ];
 
+    let multi_token_claims = has_multi_token_claims(&state);
     match collection.aggregate(pipeline, None).await {
         Ok(mut cursor) => {
             let mut res = Vec::new();
             while let Some(result) = cursor.next().await {
                 match result {
-                    Ok(document) => {
-                        res.push(document);
+                    Ok(mut document) => {
+                        let claims = document.remove("boost_claims");
+                        if has_pending_tokens(&document, claims, multi_token_claims) {
+                            res.push(document);
+                        }
                     }
                     _ => continue,
                 }
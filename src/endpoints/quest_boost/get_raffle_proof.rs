@@ -0,0 +1,78 @@
+use crate::{
+    common::raffle::run_raffle,
+    models::{AppState, BoostTable},
+    utils::{get_error, to_hex},
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use axum_auto_routes::route;
+use mongodb::bson::doc;
+use serde::Deserialize;
+use serde_json::json;
+use starknet::core::types::FieldElement;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub struct GetRaffleProofQuery {
+    boost_id: u32,
+}
+
+#[route(get, "/boost/raffle_proof")]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<GetRaffleProofQuery>,
+) -> impl IntoResponse {
+    let collection = state.db.collection::<BoostTable>("boosts");
+    let boost = match collection
+        .find_one(doc! { "id": query.boost_id }, None)
+        .await
+    {
+        Ok(Some(boost)) => boost,
+        Ok(None) => return get_error(format!("Boost with id {} not found", query.boost_id)),
+        Err(_) => return get_error("Error querying boost".to_string()),
+    };
+
+    let (Some(raffle), Some(winners)) = (boost.raffle, boost.winner) else {
+        return get_error("Raffle has not been drawn for this boost".to_string());
+    };
+
+    // winners are recomputed from the stored inputs, which is what any third party can do
+    let participants: Result<Vec<FieldElement>, _> = raffle
+        .participants
+        .iter()
+        .map(|addr| FieldElement::from_str(addr))
+        .collect();
+    let verified = match (participants, FieldElement::from_str(&raffle.seed)) {
+        (Ok(participants), Ok(seed)) => run_raffle(
+            raffle.algorithm_version,
+            &participants,
+            raffle.weights.as_deref(),
+            seed,
+            boost.num_of_winners as usize,
+        )
+        .map(|recomputed| recomputed.into_iter().map(to_hex).collect::<Vec<String>>() == winners)
+        .unwrap_or(false),
+        _ => false,
+    };
+
+    (
+        StatusCode::OK,
+        Json(json!({
+            "boost_id": boost.id,
+            "algorithm_version": raffle.algorithm_version,
+            "block_number": raffle.block_number,
+            "seed": raffle.seed,
+            "num_of_winners": boost.num_of_winners,
+            "weighting": boost.weighting,
+            "participants": raffle.participants,
+            "weights": raffle.weights,
+            "winners": winners,
+            "verified": verified,
+        })),
+    )
+        .into_response()
+}
@@ -4,3 +4,4 @@ pub mod get_claim_params;
 pub mod get_completed_boosts;
 pub mod get_pending_claims;
 pub mod get_quests;
+pub mod get_raffle_proof;
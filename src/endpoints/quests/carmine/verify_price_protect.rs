@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::{
@@ -14,7 +15,7 @@ use axum_auto_routes::route;
 use serde_json::json;
 use starknet::core::types::FieldElement;
 
-#[route(get, "/quests/carmine/verify_price_protect")]
+#[route(get, "/quests/carmine/verify_price_protect", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyQuery>,
@@ -5,12 +5,13 @@ use axum::{
     Json,
 };
 
-use crate::models::{Reward, RewardResponse};
+use crate::models::{NFTUri, QuestDocument, QuestTaskDocument, Reward, RewardResponse};
 use crate::utils::get_nft;
+use axum::http::StatusCode;
 use axum_auto_routes::route;
 use futures::TryStreamExt;
 use mongodb::bson::{doc, Document};
-use axum::http::StatusCode;
+use mongodb::options::FindOptions;
 use serde::{Deserialize, Serialize};
 use starknet::core::types::FieldElement;
 use starknet::signers::{LocalWallet, SigningKey};
@@ -22,162 +23,146 @@ pub struct HasCompletedQuestsQuery {
     quest_id: u32,
 }
 
+// pairs every NFT of the quest with the task it is signed for. The NFT contract only allows one
+// mint per (quest, task, address), so each NFT of a multi-NFT quest needs a different task
+fn get_nft_rewards(uris: &[NFTUri], task_ids: &[u32]) -> Result<Vec<(u32, u32)>, String> {
+    let mut nft_levels: Vec<u32> = vec![];
+    for uri in uris {
+        if !nft_levels.contains(&(uri.id as u32)) {
+            nft_levels.push(uri.id as u32);
+        }
+    }
+    if nft_levels.is_empty() {
+        return Err("No rewards found for this quest".to_string());
+    }
+    if nft_levels.len() > task_ids.len() {
+        return Err("This quest grants more NFTs than it has tasks".to_string());
+    }
+    Ok(task_ids.iter().copied().zip(nft_levels).collect())
+}
+
 #[route(get, "/quests/claimable")]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<HasCompletedQuestsQuery>,
 ) -> impl IntoResponse {
-    let address = query.addr.to_string();
     let quest_id = query.quest_id;
-    let pipeline = vec![
-        doc! {
-            "$match": doc! {
-                "address": address
-            }
-        },
-        doc! {
-            "$lookup": doc! {
-                "from": "tasks",
-                "localField": "task_id",
-                "foreignField": "id",
-                "as": "associatedTask"
-            }
-        },
-        doc! {
-            "$unwind": "$associatedTask"
-        },
-        doc! {
-            "$project": doc! {
-                "_id": 0,
-                "address": 1,
-                "task_id": 1,
-                "quest_id": "$associatedTask.quest_id"
-            }
-        },
-        doc! {
-            "$group": doc! {
-                "_id": "$quest_id",
-                "done": doc! {
-                    "$sum": 1
-                }
-            }
-        },
-        doc! {
-            "$match": doc! {
-                "_id": quest_id
-            }
-        },
-        doc! {
-            "$lookup": doc! {
-                "from": "tasks",
-                "localField": "_id",
-                "foreignField": "quest_id",
-                "as": "tasks"
-            }
-        },
-        doc! {
-            "$project": doc! {
-                "_id": 1,
-                "tasks": "$tasks",
-                "result": doc! {
-                    "$cond": doc! {
-                        "if": doc! {
-                            "$eq": [
-                                doc! {
-                                    "$size": "$tasks"
-                                },
-                                "$done"
-                            ]
-                        },
-                        "then": true,
-                        "else": false
-                    }
-                }
-            }
-        },
-        doc! {
-            "$unwind": doc! {
-                "path": "$tasks"
-            }
-        },
-        doc! {
-            "$sort": doc! {
-                "tasks.id": -1
-            }
-        },
-        doc! {
-            "$limit": 1
-        },
-        doc! {
-            "$lookup": doc! {
-                "from": "nft_uri",
-                "localField": "_id",
-                "foreignField": "quest_id",
-                "as": "nft_uri"
-            }
-        },
-        doc! {
-            "$project": doc! {
-                "result": 1,
-                "_id": 0,
-                "last_task": "$tasks.id",
-                "nft_level": "$nft_uri.id"
-            }
+
+    let quests_collection = state.db.collection::<QuestDocument>("quests");
+    match quests_collection
+        .find_one(doc! { "id": quest_id }, None)
+        .await
+    {
+        Ok(Some(_)) => {}
+        Ok(None) => return get_error("Quest not found".to_string()),
+        Err(_) => return get_error("Error querying quest".to_string()),
+    }
+
+    // tasks are sorted from the last one so the last task keeps signing the first NFT
+    let tasks_collection = state.db.collection::<QuestTaskDocument>("tasks");
+    let options = FindOptions::builder().sort(doc! { "id": -1 }).build();
+    let task_ids: Vec<u32> = match tasks_collection
+        .find(doc! { "quest_id": quest_id }, options)
+        .await
+    {
+        Ok(cursor) => match cursor.try_collect::<Vec<QuestTaskDocument>>().await {
+            Ok(tasks) => tasks.iter().map(|task| task.id as u32).collect(),
+            Err(_) => return get_error("Error querying tasks".to_string()),
         },
-        doc! {
-            "$unwind": doc! {
-                "path": "$nft_level"
-            }
+        Err(_) => return get_error("Error querying tasks".to_string()),
+    };
+    if task_ids.is_empty() {
+        return get_error("This quest has no tasks".to_string());
+    }
+
+    let completed_tasks_collection = state.db.collection::<Document>("completed_tasks");
+    let filter = doc! {
+        "address": query.addr.to_string(),
+        "task_id": { "$in": task_ids.clone() },
+    };
+    // distinct task ids so that duplicate completion rows are only counted once
+    match completed_tasks_collection
+        .distinct("task_id", filter, None)
+        .await
+    {
+        Ok(done) if done.len() == task_ids.len() => {}
+        Ok(_) => return get_error("User hasn't completed all tasks".to_string()),
+        Err(_) => return get_error("Error querying status".to_string()),
+    }
+
+    // the level signed is the id of an nft_uri, which /quests/uri resolves the metadata from
+    let uri_collection = state.db.collection::<NFTUri>("nft_uri");
+    let options = FindOptions::builder().sort(doc! { "id": 1 }).build();
+    let uris = match uri_collection
+        .find(doc! { "quest_id": quest_id }, options)
+        .await
+    {
+        Ok(cursor) => match cursor.try_collect::<Vec<NFTUri>>().await {
+            Ok(uris) => uris,
+            Err(_) => return get_error("Error querying NFT levels".to_string()),
         },
-    ];
-    let tasks_collection = state.db.collection::<Document>("completed_tasks");
-    match tasks_collection.aggregate(pipeline, None).await {
-        Ok(cursor) => {
-            let mut cursor = cursor;
-            let mut result = false;
-            let mut nft_level = 0;
-            let mut last_task = 0;
-            while let Some(doc) = cursor.try_next().await.unwrap() {
-                result = doc.get("result").unwrap().as_bool().unwrap();
-                nft_level = doc.get("nft_level").unwrap().as_i64().unwrap();
-                last_task = doc.get("last_task").unwrap().as_i32().unwrap();
-            }
-
-            if !result {
-                return get_error("User hasn't completed all tasks".to_string());
-            }
-
-            let signer = LocalWallet::from(SigningKey::from_secret_scalar(
-                state.conf.nft_contract.private_key,
-            ));
-
-            let mut rewards = vec![];
-
-            let Ok((token_id, sig)) = get_nft(
-                quest_id,
-                last_task as u32,
-                &query.addr,
-                nft_level as u32,
-                &signer,
-            )
-            .await
-            else {
-                return get_error("Signature failed".into());
-            };
-
-            rewards.push(Reward {
-                task_id: last_task as u32,
-                nft_contract: state.conf.nft_contract.address.clone(),
-                token_id: token_id.to_string(),
-                sig: (sig.r, sig.s),
-            });
-
-            if rewards.is_empty() {
-                get_error("No rewards found for this user".into())
-            } else {
-                (StatusCode::OK, Json(RewardResponse { rewards })).into_response()
-            }
+        Err(_) => return get_error("Error querying NFT levels".to_string()),
+    };
+    let nft_rewards = match get_nft_rewards(&uris, &task_ids) {
+        Ok(nft_rewards) => nft_rewards,
+        Err(e) => return get_error(e),
+    };
+
+    let signer = LocalWallet::from(SigningKey::from_secret_scalar(
+        state.conf.nft_contract.private_key,
+    ));
+
+    let mut rewards = vec![];
+    for (task_id, nft_level) in nft_rewards.iter() {
+        let Ok((token_id, sig)) =
+            get_nft(quest_id, *task_id, &query.addr, *nft_level, &signer).await
+        else {
+            return get_error("Signature failed".into());
+        };
+
+        rewards.push(Reward {
+            task_id: *task_id,
+            nft_contract: state.conf.nft_contract.address.clone(),
+            token_id: token_id.to_string(),
+            sig: (sig.r, sig.s),
+        });
+    }
+
+    (StatusCode::OK, Json(RewardResponse { rewards })).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nft_uri(id: i64, quest_id: i64) -> NFTUri {
+        NFTUri {
+            id,
+            name: "NFT".to_string(),
+            description: "".to_string(),
+            image: "/nft.webp".to_string(),
+            quest_id,
+            attributes: None,
         }
-        Err(_) => get_error("Error querying status".to_string()),
+    }
+
+    #[test]
+    fn test_get_nft_rewards_of_created_quest() {
+        // quests, tasks and nft_uris created through the admin endpoints share the same id
+        // counter: quest 40 gets tasks 41 and 42 then its nft_uri 43, while its rewards_nfts
+        // keep the level 1 create_quest writes
+        let rewards = get_nft_rewards(&[nft_uri(43, 40)], &[42, 41]).unwrap();
+        assert_eq!(rewards, vec![(42, 43)]);
+    }
+
+    #[test]
+    fn test_get_nft_rewards_of_multi_nft_quest() {
+        let uris = [nft_uri(7, 1), nft_uri(8, 1), nft_uri(8, 1)];
+        assert_eq!(
+            get_nft_rewards(&uris, &[3, 2, 1]).unwrap(),
+            vec![(3, 7), (2, 8)]
+        );
+        assert!(get_nft_rewards(&uris, &[3]).is_err());
+        assert!(get_nft_rewards(&[], &[3]).is_err());
     }
 }
@@ -0,0 +1,45 @@
+use crate::middleware::session::session_middleware;
+use std::sync::Arc;
+
+use crate::common::{
+    discord::{get_callback_uri, get_task},
+    linked_accounts::{get_authorize_url, get_oauth_provider},
+    oauth_state::sign_state,
+};
+use crate::models::VerifyNewQuery;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_auto_routes::route;
+use serde_json::json;
+
+// returns the discord authorization url of a task, its state being signed for the address
+#[route(get, "/quests/discord_auth", session_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<VerifyNewQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = get_task(&state, query.quest_id, query.task_id).await {
+        return get_error(e);
+    }
+    let Some(provider) = get_oauth_provider(&state, "discord") else {
+        return get_error("Unsupported provider".to_string());
+    };
+    let oauth_state = match sign_state(
+        &state,
+        query.addr.to_string(),
+        query.quest_id,
+        query.task_id,
+    ) {
+        Ok(oauth_state) => oauth_state,
+        Err(e) => return get_error(e),
+    };
+    match get_authorize_url(&provider, &get_callback_uri(&state), &oauth_state, None) {
+        Ok(url) => (StatusCode::OK, Json(json!({ "url": url }))).into_response(),
+        Err(e) => get_error(e),
+    }
+}
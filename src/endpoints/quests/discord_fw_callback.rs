@@ -1,7 +1,11 @@
 use std::sync::Arc;
 
-use crate::models::QuestTaskDocument;
-use crate::utils::CompletedTasksTrait;
+use crate::common::{
+    discord::{get_callback_uri, get_task, verify_membership},
+    linked_accounts::{get_oauth_provider, link_with_code, record_account_task},
+    oauth_state::verify_state,
+};
+use crate::utils::{get_task_redirect_uri, CompletedTasksTrait};
 use crate::{
     models::AppState,
     utils::{get_error_redirect, success_redirect},
@@ -11,149 +15,79 @@ use axum::{
     response::IntoResponse,
 };
 use axum_auto_routes::route;
-use mongodb::bson::doc;
-use reqwest::header::AUTHORIZATION;
+use mongodb::bson::{doc, Document};
 use serde::Deserialize;
 use starknet::core::types::FieldElement;
 
 #[derive(Deserialize)]
-pub struct TwitterOAuthCallbackQuery {
-    code: String,
+pub struct DiscordOAuthCallbackQuery {
+    code: Option<String>,
     state: String,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct Guild {
-    id: String,
-    #[allow(dead_code)]
-    name: String,
-}
-
+// callback of every discord task, the signed state issued by /quests/discord_auth telling which
+// task of which address is being verified
 #[route(get, "/quests/discord_fw_callback")]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
-    Query(query): Query<TwitterOAuthCallbackQuery>,
+    Query(query): Query<DiscordOAuthCallbackQuery>,
 ) -> impl IntoResponse {
-    let logger = &state.logger;
-    // the state is in format => "address+quest_id+task_id"
-    let state_split = query.state.split('+').collect::<Vec<&str>>();
-    let quest_id = state_split[1].parse::<i64>().unwrap();
-    let task_id = state_split[2].parse::<u32>().unwrap();
-    let addr = FieldElement::from_dec_str(state_split[0]).unwrap();
+    let app_link = &state.conf.variables.app_link;
+    let payload = match verify_state(&state, &query.state).await {
+        Ok(payload) => payload,
+        Err(e) => return get_error_redirect(format!("{}?res=false", app_link), e),
+    };
+    let (quest_id, task_id) = (payload.quest_id, payload.task_id);
 
-    let tasks_collection = state.db.collection::<QuestTaskDocument>("tasks");
-    let task = tasks_collection
-        .find_one(
-            doc! { "id": task_id,"quest_id":quest_id,"task_type":"discord" },
-            None,
-        )
+    let return_url = state
+        .db
+        .collection::<Document>("quests")
+        .find_one(doc! { "id": quest_id }, None)
         .await
-        .unwrap()
-        .unwrap();
+        .ok()
+        .flatten()
+        .and_then(|quest| quest.get_str("return_url").ok().map(|url| url.to_string()));
+    let redirect_uri =
+        |res: bool| get_task_redirect_uri(app_link, quest_id, return_url.as_deref(), task_id, res);
+    let error_redirect_uri = redirect_uri(false);
 
-    let guild_id = task.discord_guild_id.unwrap();
-
-    let authorization_code = &query.code;
-    let error_redirect_uri = format!(
-        "{}/quest/{}?task_id={}&res=false",
-        state.conf.variables.app_link, quest_id, task_id
-    );
-
-    // Exchange the authorization code for an access token
-    let params = [
-        ("client_id", &state.conf.discord.oauth2_clientid),
-        ("client_secret", &state.conf.discord.oauth2_secret),
-        ("code", &authorization_code.to_string()),
-        (
-            "redirect_uri",
-            &format!(
-                "{}/quests/discord_fw_callback",
-                state.conf.variables.api_link
-            ),
-        ),
-        ("grant_type", &"authorization_code".to_string()),
-    ];
-    let access_token = match exchange_authorization_code(params).await {
-        Ok(token) => token,
-        Err(e) => {
-            return get_error_redirect(
-                error_redirect_uri,
-                format!("Failed to exchange authorization code: {}", e),
-            );
-        }
+    // the code is missing when the user denied the authorization
+    let Some(code) = &query.code else {
+        return get_error_redirect(error_redirect_uri, "Authorization was denied".to_string());
     };
-
-    // Get user guild information
-    let client = reqwest::Client::new();
-    let response_result = client
-        .get("https://discord.com/api/users/@me/guilds")
-        .header(AUTHORIZATION, format!("Bearer {}", access_token))
-        .send()
-        .await;
-    let response: Vec<Guild> = match response_result {
-        Ok(response) => {
-            let json_result = response.json().await;
-            match json_result {
-                Ok(json) => json,
-                Err(e) => {
-                    return get_error_redirect(
-                        error_redirect_uri,
-                        format!(
-                            "Failed to get JSON response while fetching user info: {}",
-                            e
-                        ),
-                    );
-                }
-            }
-        }
-        Err(e) => {
-            return get_error_redirect(
-                error_redirect_uri,
-                format!("Failed to send request to get user info: {}", e),
-            );
-        }
+    let Ok(addr) = FieldElement::from_dec_str(&payload.addr) else {
+        return get_error_redirect(error_redirect_uri, "Invalid address".to_string());
+    };
+    let task = match get_task(&state, quest_id, task_id).await {
+        Ok(task) => task,
+        Err(e) => return get_error_redirect(error_redirect_uri, e),
+    };
+    let Some(provider) = get_oauth_provider(&state, "discord") else {
+        return get_error_redirect(error_redirect_uri, "Unsupported provider".to_string());
     };
 
-    for guild in response {
-        if guild.id == guild_id {
-            logger.info(format!("Checking guild: {:?}", guild));
-            match state.upsert_completed_task(addr, task_id).await {
-                Ok(_) => {
-                    let redirect_uri = format!(
-                        "{}/quest/{}?task_id={}&res=true",
-                        state.conf.variables.app_link, quest_id, task_id
-                    );
-                    return success_redirect(redirect_uri);
-                }
-                Err(e) => return get_error_redirect(error_redirect_uri, format!("{}", e)),
+    // the account is kept so that the next discord tasks are verified without OAuth
+    let callback_uri = get_callback_uri(&state);
+    let account =
+        match link_with_code(&state, &provider, &payload.addr, code, &callback_uri, None).await {
+            Ok(account) => account,
+            Err(e) => {
+                return get_error_redirect(
+                    error_redirect_uri,
+                    format!("Failed to exchange authorization code: {}", e),
+                )
             }
-        }
+        };
+    let access_token = account.access_token.clone().unwrap_or_default();
+    if let Err(e) = verify_membership(&state, &account.account_id, &access_token, &task).await {
+        return get_error_redirect(error_redirect_uri, e);
+    }
+    if let Err(e) = record_account_task(&state, &account, task_id).await {
+        return get_error_redirect(error_redirect_uri, e);
     }
 
-    get_error_redirect(
-        error_redirect_uri,
-        "You're not part of the Discord server".to_string(),
-    )
-}
-
-async fn exchange_authorization_code(
-    params: [(&str, &String); 5],
-) -> Result<String, Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
-    let res = client
-        .post("https://discord.com/api/oauth2/token")
-        .form(&params)
-        .send()
-        .await?;
-    let json: serde_json::Value = res.json().await?;
-    match json["access_token"].as_str() {
-        Some(s) => Ok(s.to_string()),
-        None => Err(Box::new(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!(
-                "Failed to get 'access_token' from JSON response : {:?}",
-                json
-            ),
-        ))),
+    match state.upsert_completed_task(addr, task_id).await {
+        Ok(_) => success_redirect(redirect_uri(true)),
+        Err(e) => get_error_redirect(error_redirect_uri, format!("{}", e)),
     }
 }
@@ -1,7 +1,9 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::{
-    models::{AppState, VerifyQuery},
+    common::block_sampling::{get_verification_blocks, sample_blocks},
+    models::{AppState, QuestTaskDocument, VerifyQuery},
     utils::{get_error, CompletedTasksTrait},
 };
 use axum::{
@@ -11,14 +13,15 @@ use axum::{
     Json,
 };
 use axum_auto_routes::route;
+use mongodb::bson::doc;
 use serde_json::json;
 use starknet::{
-    core::types::{BlockId, BlockTag, FieldElement, FunctionCall},
+    core::types::{BlockId, FieldElement, FunctionCall},
     macros::selector,
     providers::Provider,
 };
 
-#[route(get, "/quests/ekubo/verify_added_liquidity")]
+#[route(get, "/quests/ekubo/verify_added_liquidity", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyQuery>,
@@ -26,30 +29,47 @@ pub async fn handler(
     let task_id = 38;
     let addr = &query.addr;
 
-    // check if user has provider liquidity
-    let call_result = state
-        .provider
-        .call(
-            FunctionCall {
-                contract_address: state.conf.quests.ekubo.contract,
-                entry_point_selector: selector!("balanceOf"),
-                calldata: vec![*addr],
-            },
-            BlockId::Tag(BlockTag::Latest),
-        )
-        .await;
+    let task_collection = state.db.collection::<QuestTaskDocument>("tasks");
+    let task = match task_collection.find_one(doc! {"id": task_id}, None).await {
+        Ok(Some(task)) => task,
+        Ok(None) => return get_error("Task not found".to_string()),
+        Err(e) => return get_error(format!("Database error: {}", e)),
+    };
+    let blocks = match get_verification_blocks(&state, &task).await {
+        Ok(blocks) => blocks,
+        Err(e) => return get_error(e),
+    };
 
-    match call_result {
-        Ok(result) => {
-            if result[0] == FieldElement::ZERO {
-                get_error("You didn't provided any liquidity on Ekubo.".to_string())
-            } else {
-                match state.upsert_completed_task(query.addr, task_id).await {
-                    Ok(_) => (StatusCode::OK, Json(json!({"res": true}))).into_response(),
-                    Err(e) => get_error(format!("{}", e)),
-                }
-            }
+    // check if user has provider liquidity
+    let check = |block_id: BlockId| {
+        let state = state.clone();
+        let addr = *addr;
+        async move {
+            let result = state
+                .provider
+                .call(
+                    FunctionCall {
+                        contract_address: state.conf.quests.ekubo.contract,
+                        entry_point_selector: selector!("balanceOf"),
+                        calldata: vec![addr],
+                    },
+                    block_id,
+                )
+                .await
+                .map_err(|e| format!("{}", e))?;
+            Ok((result[0] != FieldElement::ZERO, result))
         }
-        Err(e) => get_error(format!("{}", e)),
+    };
+
+    match sample_blocks(&blocks, check).await {
+        Ok((true, block_samples)) => match state
+            .upsert_sampled_completed_task(query.addr, task_id, block_samples)
+            .await
+        {
+            Ok(_) => (StatusCode::OK, Json(json!({"res": true}))).into_response(),
+            Err(e) => get_error(format!("{}", e)),
+        },
+        Ok((false, _)) => get_error("You didn't provided any liquidity on Ekubo.".to_string()),
+        Err(e) => get_error(e),
     }
 }
@@ -1,2 +1 @@
-pub mod discord_fw_callback;
 pub mod verify_twitter_rt;
@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::{
@@ -13,7 +14,7 @@ use axum::{
 use axum_auto_routes::route;
 use serde_json::json;
 
-#[route(get, "/quests/focustree/verify_twitter_rt")]
+#[route(get, "/quests/focustree/verify_twitter_rt", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyQuery>,
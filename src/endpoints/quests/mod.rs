@@ -1,6 +1,7 @@
 pub mod carmine;
 pub mod claimable;
 pub mod contract_uri;
+pub mod discord_auth;
 pub mod discord_fw_callback;
 pub mod ekubo;
 pub mod focustree;
@@ -9,9 +10,12 @@ pub mod proscore;
 pub mod starknet;
 pub mod starknetid;
 pub mod uri;
+pub mod verify;
 pub mod verify_balance;
 pub mod verify_contract;
 pub mod verify_custom_api;
+pub mod verify_discord;
 pub mod verify_quiz;
+pub mod verify_telegram;
 pub mod verify_twitter_fw;
 pub mod verify_twitter_rw;
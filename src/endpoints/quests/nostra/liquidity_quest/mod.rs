@@ -1,3 +1,2 @@
 pub mod claimable;
-pub mod discord_fw_callback;
 pub mod verify_added_liquidity;
@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::{
@@ -18,7 +19,7 @@ use starknet::{
     providers::Provider,
 };
 
-#[route(get, "/quests/nostra/staking_quest/verify_stake")]
+#[route(get, "/quests/nostra/staking_quest/verify_stake", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyQuery>,
@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::{
@@ -13,7 +14,11 @@ use axum::{
 use axum_auto_routes::route;
 use serde_json::json;
 
-#[route(get, "/quests/nostra/staking_quest/verify_twitter_tw")]
+#[route(
+    get,
+    "/quests/nostra/staking_quest/verify_twitter_tw",
+    session_middleware
+)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyQuery>,
@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use std::{str::FromStr, sync::Arc};
 
 use crate::{
@@ -18,7 +19,7 @@ use starknet::{
     providers::Provider,
 };
 
-#[route(get, "/quests/proscore/verify_borrow")]
+#[route(get, "/quests/proscore/verify_borrow", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyQuery>,
@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::{
@@ -19,7 +20,7 @@ use starknet::{
     providers::Provider,
 };
 
-#[route(get, "/quests/proscore/verify_signers")]
+#[route(get, "/quests/proscore/verify_signers", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyQuery>,
@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::{
@@ -25,7 +26,7 @@ pub struct StarknetIdQuery {
     addr: FieldElement,
 }
 
-#[route(get, "/quests/starknetid/verify_has_domain")]
+#[route(get, "/quests/starknetid/verify_has_domain", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<StarknetIdQuery>,
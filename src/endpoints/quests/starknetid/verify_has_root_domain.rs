@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use crate::{
     common::verify_has_root_domain::execute_has_root_domain,
     models::{AppState, VerifyQuery},
@@ -9,7 +10,7 @@ use axum::{
 use axum_auto_routes::route;
 use std::sync::Arc;
 
-#[route(get, "/quests/starknetid/verify_has_root_domain")]
+#[route(get, "/quests/starknetid/verify_has_root_domain", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyQuery>,
@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use crate::{
     models::{AppState, VerifyQuery},
     utils::{get_error, CompletedTasksTrait},
@@ -38,7 +39,7 @@ async fn call_contract_helper(
     result.map_err(|e| format!("{}", e))
 }
 
-#[route(get, "/quests/starknetid/verify_socials")]
+#[route(get, "/quests/starknetid/verify_socials", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyQuery>,
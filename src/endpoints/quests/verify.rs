@@ -0,0 +1,52 @@
+use crate::middleware::session::session_middleware;
+use std::sync::Arc;
+
+use crate::{
+    common::verification_rules::evaluate_rule,
+    models::{AppState, QuestTaskDocument},
+    utils::{get_error, CompletedTasksTrait},
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_auto_routes::route;
+use mongodb::bson::doc;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use starknet::core::types::FieldElement;
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct VerifyRuleQuery {
+    pub addr: FieldElement,
+    pub task_id: u32,
+}
+
+#[route(get, "/quests/verify", session_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<VerifyRuleQuery>,
+) -> impl IntoResponse {
+    let task_id = query.task_id;
+    let task_collection = state.db.collection::<QuestTaskDocument>("tasks");
+    let task = match task_collection.find_one(doc! {"id": task_id}, None).await {
+        Ok(Some(task)) => task,
+        Ok(None) => return get_error("Task not found".to_string()),
+        Err(e) => return get_error(format!("Database error: {}", e)),
+    };
+
+    let Some(rule) = task.rule else {
+        return get_error("No verification rule specified for this task.".to_string());
+    };
+
+    match evaluate_rule(&state, &rule, query.addr).await {
+        Ok(true) => match state.upsert_completed_task(query.addr, task_id).await {
+            Ok(_) => (StatusCode::OK, Json(json!({"res": true}))).into_response(),
+            Err(e) => get_error(format!("Failed to update completed task: {}", e)),
+        },
+        Ok(false) => get_error("User not eligible.".to_string()),
+        Err(e) => get_error(e),
+    }
+}
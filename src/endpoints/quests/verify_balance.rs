@@ -1,6 +1,8 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::{
+    common::block_sampling::{get_verification_blocks, sample_blocks},
     models::{AppState, QuestTaskDocument},
     utils::{get_error, CompletedTasksTrait},
 };
@@ -15,7 +17,7 @@ use mongodb::bson::doc;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use starknet::{
-    core::types::{BlockId, BlockTag, FieldElement, FunctionCall},
+    core::types::{BlockId, FieldElement, FunctionCall},
     macros::selector,
     providers::Provider,
 };
@@ -26,7 +28,7 @@ pub struct VerifyBalanceQuery {
     pub task_id: u32,
 }
 
-#[route(get, "/quests/verify_balance")]
+#[route(get, "/quests/verify_balance", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyBalanceQuery>,
@@ -44,40 +46,50 @@ pub async fn handler(
         return get_error("Invalid task type.".to_string());
     }
 
+    let blocks = match get_verification_blocks(&state, &task).await {
+        Ok(blocks) => blocks,
+        Err(e) => return get_error(e),
+    };
+
     let addr = &query.addr;
     let utils_contract = state.conf.quests.utils_contract;
 
     let mut calldata = vec![addr.clone(), task.contracts.clone().unwrap().len().into()];
     calldata.append(&mut task.contracts.unwrap().clone());
 
-    let call_result = state
-        .provider
-        .call(
-            FunctionCall {
-                contract_address: utils_contract,
-                entry_point_selector: selector!("sum_balances"),
-                calldata,
-            },
-            BlockId::Tag(BlockTag::Latest),
-        )
-        .await;
-
     let required_amount = task
         .total_amount
         .unwrap_or_else(|| FieldElement::from_dec_str("3000000000000000").unwrap());
 
-    match call_result {
-        Ok(result) => {
-            // if result[0] < FieldElement::from_dec_str("3000000000000000").unwrap() {
-            if result[0] < required_amount {
-                get_error("You didn't invest (enough).".to_string())
-            } else {
-                match state.upsert_completed_task(query.addr, task_id).await {
-                    Ok(_) => (StatusCode::OK, Json(json!({"res": true}))).into_response(),
-                    Err(e) => get_error(format!("{}", e)),
-                }
-            }
+    let check = |block_id: BlockId| {
+        let state = state.clone();
+        let calldata = calldata.clone();
+        async move {
+            let result = state
+                .provider
+                .call(
+                    FunctionCall {
+                        contract_address: utils_contract,
+                        entry_point_selector: selector!("sum_balances"),
+                        calldata,
+                    },
+                    block_id,
+                )
+                .await
+                .map_err(|e| format!("{}", e))?;
+            Ok((result[0] >= required_amount, result))
         }
-        Err(e) => get_error(format!("{}", e)),
+    };
+
+    match sample_blocks(&blocks, check).await {
+        Ok((true, block_samples)) => match state
+            .upsert_sampled_completed_task(query.addr, task_id, block_samples)
+            .await
+        {
+            Ok(_) => (StatusCode::OK, Json(json!({"res": true}))).into_response(),
+            Err(e) => get_error(format!("{}", e)),
+        },
+        Ok((false, _)) => get_error("You didn't invest (enough).".to_string()),
+        Err(e) => get_error(e),
     }
 }
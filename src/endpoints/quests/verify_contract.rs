@@ -1,8 +1,11 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
+use crate::common::verification_rules::evaluate_predicate;
 use crate::utils::parse_string;
 use crate::{
-    models::{AppState, QuestTaskDocument},
+    common::block_sampling::{get_verification_blocks, sample_blocks},
+    models::{AppState, Call, QuestTaskDocument},
     utils::{get_error, CompletedTasksTrait},
 };
 use axum::{
@@ -17,17 +20,45 @@ use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use starknet::{
-    core::types::{BlockId, BlockTag, FieldElement, FunctionCall},
+    core::types::{BlockId, FieldElement, FunctionCall},
     providers::Provider,
 };
 
+// checks a call result against its predicates, or its regex for older tasks
+fn check_call_result(call: &Call, result: &[FieldElement]) -> Result<bool, String> {
+    match (&call.predicates, &call.regex) {
+        (Some(predicates), _) if !predicates.is_empty() => {
+            for predicate in predicates {
+                if !evaluate_predicate(result, predicate)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        (_, Some(regex)) => {
+            let regex_str = parse_string(regex, FieldElement::from_hex_be(&call.contract).unwrap());
+            let regex = match Regex::new(&regex_str) {
+                Ok(re) => re,
+                Err(e) => return Err(format!("Invalid regex: {}", e)),
+            };
+            let result_str = result
+                .iter()
+                .map(|&r| r.to_string())
+                .collect::<Vec<String>>()
+                .join(",");
+            Ok(regex.is_match(&result_str))
+        }
+        _ => Err("No predicate specified for this call.".to_string()),
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct VerifyContractQuery {
     pub addr: FieldElement,
     pub task_id: u32,
 }
 
-#[route(get, "/quests/verify_contract")]
+#[route(get, "/quests/verify_contract", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyContractQuery>,
@@ -45,75 +76,83 @@ pub async fn handler(
     }
 
     let addr = &query.addr;
-    if let Some(calls) = task.calls {
-        for call in calls {
-            let contract_address = match FieldElement::from_hex_be(&call.contract) {
-                Ok(address) => address,
-                Err(e) => return get_error(format!("Invalid contract address: {}", e)),
-            };
+    let Some(calls) = task.calls.clone() else {
+        return get_error("No calls specified for this task.".to_string());
+    };
 
-            let calldata: Vec<FieldElement> = match call
-                .call_data
-                .iter()
-                .map(|s| {
-                    let replaced_calldata = parse_string(s, FieldElement::from_hex_be(s).unwrap());
-                    FieldElement::from_hex_be(&replaced_calldata)
-                })
-                .collect::<Result<Vec<FieldElement>, _>>()
-            {
-                Ok(data) => data,
-                Err(e) => return get_error(format!("Invalid calldata: {}", e)),
-            };
+    let mut function_calls = vec![];
+    for call in calls {
+        let contract_address = match FieldElement::from_hex_be(&call.contract) {
+            Ok(address) => address,
+            Err(e) => return get_error(format!("Invalid contract address: {}", e)),
+        };
 
-            let entry_point_selector = match FieldElement::from_hex_be(&call.entry_point) {
-                Ok(selector) => selector,
-                Err(e) => return get_error(format!("Invalid entry point: {}", e)),
-            };
+        let calldata: Vec<FieldElement> = match call
+            .call_data
+            .iter()
+            .map(|s| {
+                let replaced_calldata = parse_string(s, *addr);
+                FieldElement::from_hex_be(&replaced_calldata)
+            })
+            .collect::<Result<Vec<FieldElement>, _>>()
+        {
+            Ok(data) => data,
+            Err(e) => return get_error(format!("Invalid calldata: {}", e)),
+        };
 
-            let call_result = state
-                .provider
-                .call(
-                    FunctionCall {
-                        contract_address,
-                        entry_point_selector,
-                        calldata,
-                    },
-                    BlockId::Tag(BlockTag::Latest),
-                )
-                .await;
+        let entry_point_selector = match FieldElement::from_hex_be(&call.entry_point) {
+            Ok(selector) => selector,
+            Err(e) => return get_error(format!("Invalid entry point: {}", e)),
+        };
 
-            match call_result {
-                Ok(result) => {
-                    let regex_str = parse_string(
-                        &call.regex,
-                        FieldElement::from_hex_be(&call.contract).unwrap(),
-                    );
-                    let regex = match Regex::new(&regex_str) {
-                        Ok(re) => re,
-                        Err(e) => return get_error(format!("Invalid regex: {}", e)),
-                    };
-                    let result_str = result
-                        .iter()
-                        .map(|&r| r.to_string())
-                        .collect::<Vec<String>>()
-                        .join(",");
+        function_calls.push((
+            call,
+            FunctionCall {
+                contract_address,
+                entry_point_selector,
+                calldata,
+            },
+        ));
+    }
+
+    let blocks = match get_verification_blocks(&state, &task).await {
+        Ok(blocks) => blocks,
+        Err(e) => return get_error(e),
+    };
 
-                    if !regex.is_match(&result_str) {
-                        return get_error(
-                            "Contract call result does not match the expected pattern.".to_string(),
-                        );
-                    }
+    let check = |block_id: BlockId| {
+        let state = state.clone();
+        let function_calls = function_calls.clone();
+        async move {
+            let mut results = vec![];
+            for (call, function_call) in function_calls {
+                let result = state
+                    .provider
+                    .call(function_call, block_id)
+                    .await
+                    .map_err(|e| format!("Contract call failed: {}", e))?;
+                let passed = check_call_result(&call, &result)?;
+                results.extend(result);
+                if !passed {
+                    return Ok((false, results));
                 }
-                Err(e) => return get_error(format!("Contract call failed: {}", e)),
             }
+            Ok((true, results))
         }
+    };
 
-        // All calls succeeded and matched their regexes
-        match state.upsert_completed_task(*addr, task_id).await {
+    match sample_blocks(&blocks, check).await {
+        // All calls succeeded and matched their predicates
+        Ok((true, block_samples)) => match state
+            .upsert_sampled_completed_task(*addr, task_id, block_samples)
+            .await
+        {
             Ok(_) => (StatusCode::OK, Json(json!({"res": true}))).into_response(),
             Err(e) => get_error(format!("Failed to update completed task: {}", e)),
+        },
+        Ok((false, _)) => {
+            get_error("Contract call result does not match the expected value.".to_string())
         }
-    } else {
-        get_error("No calls specified for this task.".to_string())
+        Err(e) => get_error(e),
     }
 }
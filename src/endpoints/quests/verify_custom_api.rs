@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use crate::utils::parse_string;
 use crate::{
     models::{AppState, QuestTaskDocument},
@@ -24,7 +25,7 @@ pub struct VerifyCustomApiQuery {
     pub task_id: u32,
 }
 
-#[route(get, "/quests/verify_custom_api")]
+#[route(get, "/quests/verify_custom_api", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyCustomApiQuery>,
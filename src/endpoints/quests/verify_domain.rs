@@ -1,3 +1,4 @@
+use crate::middleware::session::session_middleware;
 use crate::{
     common::verify_has_root_or_braavos_domain::verify_has_root_or_braavos_domain,
     models::{AppState, VerifyQuery},
@@ -9,7 +10,7 @@ use axum::{
 use axum_auto_routes::route;
 use std::sync::Arc;
 
-#[route(get, "/quests/verify_has_domain")]
+#[route(get, "/quests/verify_has_domain", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
     Query(query): Query<VerifyNewQuery>,
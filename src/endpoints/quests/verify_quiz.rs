@@ -1,26 +1,40 @@
+use crate::middleware::session::session_middleware;
 use std::sync::Arc;
 
 use crate::models::QuestTaskDocument;
 use crate::{
     common::verify_quiz::verify_quiz,
-    models::{AppState, VerifyQuizQuery},
+    models::{AppState, VerifyQuizQuery, WalletSession},
     utils::{get_error, CompletedTasksTrait},
 };
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::{Extension, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
 use axum_auto_routes::route;
 use futures::TryStreamExt;
 use mongodb::bson::doc;
 use serde_json::json;
 use starknet::core::types::FieldElement;
 
-#[route(post, "/quests/verify_quiz")]
+#[route(post, "/quests/verify_quiz", session_middleware)]
 pub async fn handler(
     State(state): State<Arc<AppState>>,
+    Extension(session): Extension<WalletSession>,
     body: Json<VerifyQuizQuery>,
 ) -> impl IntoResponse {
     if body.addr == FieldElement::ZERO {
         return get_error("Please connect your wallet first".to_string());
     }
+    if body.addr != session.addr {
+        return (
+            StatusCode::FORBIDDEN,
+            "Session does not match the provided address",
+        )
+            .into_response();
+    }
 
     let pipeline = vec![doc! {
         "$match": doc! {
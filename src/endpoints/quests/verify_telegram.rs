@@ -0,0 +1,25 @@
+use crate::middleware::session::session_middleware;
+use std::sync::Arc;
+
+use crate::common::telegram::verify_task;
+use crate::models::VerifyNewQuery;
+use crate::{models::AppState, utils::get_error};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_auto_routes::route;
+use serde_json::json;
+
+#[route(get, "/quests/verify_telegram", session_middleware)]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<VerifyNewQuery>,
+) -> impl IntoResponse {
+    match verify_task(&state, query.addr, query.quest_id, query.task_id).await {
+        Ok(_) => (StatusCode::OK, Json(json!({"res": true}))).into_response(),
+        Err(e) => get_error(e),
+    }
+}
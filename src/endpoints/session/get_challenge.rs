@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::{
+    common::verify_signature::get_challenge_typed_data,
+    models::{AppState, SessionChallengeDocument},
+    utils::{get_error, to_hex},
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use axum_auto_routes::route;
+use chrono::Utc;
+use mongodb::{bson::doc, options::UpdateOptions};
+use serde::Deserialize;
+use serde_json::json;
+use starknet::core::types::FieldElement;
+
+// challenges must be signed within 5 minutes
+const CHALLENGE_DURATION: i64 = 300;
+
+#[derive(Deserialize)]
+pub struct GetChallengeQuery {
+    addr: FieldElement,
+}
+
+#[route(get, "/session/get_challenge")]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<GetChallengeQuery>,
+) -> impl IntoResponse {
+    if query.addr == FieldElement::ZERO {
+        return get_error("Please connect your wallet first".to_string());
+    }
+
+    let nonce = FieldElement::from(rand::random::<u128>());
+    let expiry = Utc::now().timestamp() + CHALLENGE_DURATION;
+
+    // only the latest challenge of an address can be used to log in
+    let collection = state
+        .db
+        .collection::<SessionChallengeDocument>("session_challenges");
+    let filter = doc! { "addr": to_hex(query.addr) };
+    let update = doc! { "$set": { "nonce": to_hex(nonce), "expiry": expiry } };
+    let options = UpdateOptions::builder().upsert(true).build();
+
+    match collection.update_one(filter, update, options).await {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(json!({
+                "typed_data": get_challenge_typed_data(state.conf.variables.is_testnet, nonce, expiry)
+            })),
+        )
+            .into_response(),
+        Err(e) => get_error(format!("Error creating challenge: {}", e)),
+    }
+}
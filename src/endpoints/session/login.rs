@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use crate::{
+    common::verify_signature::{execute_is_valid_signature, get_challenge_message_hash},
+    models::{AppState, SessionChallengeDocument, SessionClaims},
+    utils::{get_error, to_hex},
+};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum_auto_routes::route;
+use chrono::Utc;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use mongodb::bson::doc;
+use serde::Deserialize;
+use serde_json::json;
+use starknet::core::types::FieldElement;
+
+pub_struct!(Deserialize; SessionLoginQuery {
+    addr: FieldElement,
+    signature: Vec<FieldElement>,
+});
+
+#[route(post, "/session/login")]
+pub async fn handler(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<SessionLoginQuery>,
+) -> impl IntoResponse {
+    let addr = to_hex(body.addr);
+
+    let collection = state
+        .db
+        .collection::<SessionChallengeDocument>("session_challenges");
+    let challenge = match collection.find_one(doc! { "addr": &addr }, None).await {
+        Ok(Some(challenge)) => challenge,
+        Ok(None) => return get_error("No challenge found for this address".to_string()),
+        Err(e) => return get_error(format!("Error querying challenge: {}", e)),
+    };
+    if challenge.expiry < Utc::now().timestamp() {
+        return get_error("Challenge has expired".to_string());
+    }
+
+    let Ok(nonce) = FieldElement::from_hex_be(&challenge.nonce) else {
+        return get_error("Invalid challenge nonce".to_string());
+    };
+    let hash = match get_challenge_message_hash(
+        state.conf.variables.is_testnet,
+        body.addr,
+        nonce,
+        challenge.expiry,
+    ) {
+        Ok(hash) => hash,
+        Err(e) => return get_error(e),
+    };
+
+    match execute_is_valid_signature(&state, body.addr, hash, &body.signature).await {
+        Ok(true) => {}
+        Ok(false) | Err(_) => {
+            return (StatusCode::UNAUTHORIZED, "Invalid signature").into_response()
+        }
+    }
+
+    // the challenge is only consumed once signed, so that anyone knowing the address cannot burn
+    // it, and deleting it by nonce makes a replay of the same signature fail
+    match collection
+        .delete_one(doc! { "addr": &addr, "nonce": &challenge.nonce }, None)
+        .await
+    {
+        Ok(result) if result.deleted_count == 1 => {}
+        Ok(_) => return get_error("Challenge was already used".to_string()),
+        Err(e) => return get_error(format!("Error consuming challenge: {}", e)),
+    }
+
+    let claims = SessionClaims {
+        sub: addr,
+        exp: (Utc::now().timestamp() + state.conf.auth.get_session_expiry_duration()) as usize,
+    };
+    match encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(state.conf.auth.get_session_secret_key().as_bytes()),
+    ) {
+        Ok(token) => (
+            StatusCode::OK,
+            Json(json!({ "token": token, "expiry": claims.exp })),
+        )
+            .into_response(),
+        Err(e) => get_error(format!("Error creating session: {}", e)),
+    }
+}
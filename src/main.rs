@@ -7,8 +7,15 @@ mod logger;
 mod middleware;
 mod models;
 
+use crate::common::achievement_claims::run_achievement_claims_indexer;
+use crate::common::boost_claims::run_boost_claims_indexer;
+use crate::common::linked_accounts::add_linked_accounts_indexes;
+use crate::common::oauth_state::add_used_states_index;
+use crate::common::quest_completion::add_quest_completions_index;
+use crate::common::starknetid::add_profile_cache_index;
+use crate::common::xp_ledger::add_ledger_indexes;
 use crate::utils::{add_leaderboard_table, run_boosts_raffle};
-use axum::{http::StatusCode, Router};
+use axum::{http::StatusCode, Extension, Router};
 use axum_auto_routes::route;
 use mongodb::{bson::doc, options::ClientOptions, Client};
 use reqwest::Url;
@@ -76,13 +83,17 @@ async fn main() {
         logger.info("Connected to database");
     }
 
-    let db_instance = shared_state.db.clone();
-    run_boosts_raffle(
-        &db_instance,
-        conf.quest_boost.update_interval,
-        logger.clone(),
-    );
+    run_boosts_raffle(shared_state.clone(), conf.quest_boost.update_interval);
+    run_boost_claims_indexer(shared_state.clone());
+    run_achievement_claims_indexer(shared_state.clone());
     add_leaderboard_table(&shared_state.db).await;
+    add_profile_cache_index(&shared_state.db).await;
+    if let Err(e) = add_quest_completions_index(&shared_state.db).await {
+        logger.severe(e);
+    }
+    add_ledger_indexes(&shared_state.db).await;
+    add_linked_accounts_indexes(&shared_state.db).await;
+    add_used_states_index(&shared_state.db).await;
 
     let cors = CorsLayer::new().allow_headers(Any).allow_origin(Any);
     let app = ROUTE_REGISTRY
@@ -93,6 +104,8 @@ async fn main() {
         .fold(Router::new().with_state(shared_state.clone()), |acc, r| {
             acc.merge(r.to_router(shared_state.clone()))
         })
+        // route middlewares have no access to the router state
+        .layer(Extension(shared_state.clone()))
         .layer(cors);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], conf.server.port));
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use crate::models::{AppState, SessionClaims, WalletSession};
+use axum::{
+    extract::Query,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use jsonwebtoken::{decode, DecodingKey, Validation};
+use serde::Deserialize;
+use starknet::core::types::FieldElement;
+
+#[derive(Deserialize)]
+struct SessionQuery {
+    addr: Option<FieldElement>,
+}
+
+pub async fn session_middleware<B>(
+    mut req: Request<B>,
+    next: Next<B>,
+) -> Result<Response, (StatusCode, String)> {
+    let state = req.extensions().get::<Arc<AppState>>().cloned().ok_or((
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Missing application state".to_string(),
+    ))?;
+    let secret_key = state.conf.auth.get_session_secret_key();
+
+    let token = req
+        .headers()
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .ok_or((
+            StatusCode::UNAUTHORIZED,
+            "Missing wallet session token".to_string(),
+        ))?;
+
+    let claims = match decode::<SessionClaims>(
+        token.trim(),
+        &DecodingKey::from_secret(secret_key.as_bytes()),
+        &Validation::new(jsonwebtoken::Algorithm::HS256),
+    ) {
+        Ok(token_data) => token_data.claims,
+        Err(_) => {
+            return Err((
+                StatusCode::UNAUTHORIZED,
+                "Invalid wallet session token".to_string(),
+            ))
+        }
+    };
+
+    let session_addr = FieldElement::from_hex_be(&claims.sub).map_err(|_| {
+        (
+            StatusCode::UNAUTHORIZED,
+            "Invalid wallet session token".to_string(),
+        )
+    })?;
+
+    // an address in the query must be the one that signed the challenge, handlers taking it from
+    // their body compare it with the WalletSession themselves
+    match Query::<SessionQuery>::try_from_uri(req.uri()) {
+        Ok(Query(SessionQuery { addr: Some(addr) })) if addr != session_addr => {
+            return Err((
+                StatusCode::FORBIDDEN,
+                "Session does not match the provided address".to_string(),
+            ))
+        }
+        Ok(_) => {}
+        Err(_) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "Invalid address was provided".to_string(),
+            ))
+        }
+    }
+
+    req.extensions_mut()
+        .insert(WalletSession { addr: session_addr });
+    Ok(next.run(req).await)
+}
@@ -1,5 +1,5 @@
-use mongodb::Database;
-use serde::{Deserialize, Serialize};
+use mongodb::{bson::oid::ObjectId, Database};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use starknet::{
     core::types::FieldElement,
@@ -46,6 +46,9 @@ pub_struct!(Debug, Serialize, Deserialize; QuestDocument {
     expired: Option<bool>,
     experience: i64,
     start_time: i64,
+    snapshot_block: Option<i64>,
+    // page users are sent back to once a task is verified through an OAuth provider
+    return_url: Option<String>,
 });
 
 pub_struct!(Debug, Serialize, Deserialize; QuestInsertDocument {
@@ -68,6 +71,8 @@ pub_struct!(Debug, Serialize, Deserialize; QuestInsertDocument {
     mandatory_domain: Option<String>,
     experience: i32,
     start_time: i64,
+    snapshot_block: Option<i64>,
+    return_url: Option<String>,
 });
 
 pub_struct!(Debug, Serialize, Deserialize;  QuizInsertDocument {
@@ -114,7 +119,29 @@ pub struct Call {
     pub contract: String,
     pub call_data: Vec<String>,
     pub entry_point: String,
-    pub regex: String,
+    #[serde(default)]
+    pub regex: Option<String>,
+    #[serde(default)]
+    pub predicates: Option<Vec<ResultPredicate>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultType {
+    Felt,
+    U256,
+    Bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ResultPredicate {
+    pub index: u32,
+    #[serde(rename = "type")]
+    pub result_type: ResultType,
+    pub operator: ComparisonOperator,
+    pub value: String,
+    #[serde(default)]
+    pub decimals: Option<u32>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -144,10 +171,75 @@ pub struct QuestTaskDocument {
     pub task_type: Option<String>,
     #[serde(default)]
     pub(crate) discord_guild_id: Option<String>,
+    // roles of the guild the member must have any of, not all of
+    #[serde(default)]
+    pub(crate) discord_role_ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub(crate) telegram_chat_id: Option<String>,
     #[serde(default)]
     pub(crate) contracts: Option<Vec<FieldElement>>,
     pub api_url: Option<String>,
     pub regex: Option<String>,
+    #[serde(default)]
+    pub rule: Option<VerificationRule>,
+    #[serde(default)]
+    pub block_sampling: Option<BlockSampling>,
+    #[serde(default)]
+    pub twitter_action: Option<TwitterAction>,
+}
+
+pub_struct!(Clone, Debug, Serialize, Deserialize; BlockSampling {
+    samples: u32,
+    window: i64,
+});
+
+pub_struct!(Clone, Debug, Serialize, Deserialize; BlockSample {
+    block_number: u64,
+    result: Vec<String>,
+    passed: bool,
+});
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonOperator {
+    Eq,
+    Neq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VerificationRule {
+    ContractCall {
+        contract: String,
+        entry_point: String,
+        call_data: Vec<String>,
+        result_index: u32,
+        // how the result is read, a single felt when not set
+        #[serde(default)]
+        result_type: Option<ResultType>,
+        operator: ComparisonOperator,
+        value: String,
+        // decimals of a decimal value, e.g. 18 for an amount of STRK
+        #[serde(default)]
+        decimals: Option<u32>,
+    },
+    HttpProbe {
+        url: String,
+        // JSON pointer of the value compared, like the source_field of achievements
+        field: String,
+        operator: ComparisonOperator,
+        value: Value,
+    },
+    And {
+        rules: Vec<VerificationRule>,
+    },
+    Or {
+        rules: Vec<VerificationRule>,
+    },
 }
 
 pub_struct!(Serialize; Reward {
@@ -161,6 +253,13 @@ pub_struct!(Serialize; RewardResponse {
     rewards: Vec<Reward>,
 });
 
+pub_struct!(Serialize; AchievementReward {
+    achievement_id: u32,
+    nft_contract: String,
+    token_id: String,
+    sig: (FieldElement, FieldElement),
+});
+
 pub_struct!(Deserialize; VerifyQuery {
     addr: FieldElement,
     task_id: Option<u32>,
@@ -221,6 +320,19 @@ pub_struct!(Debug, Serialize, Deserialize; AchievementDocument {
     done_desc: String,
     verify_type: String,
     experience:i64,
+    verify_params: Option<AchievementVerifyParams>,
+    nft_level: Option<u32>,
+});
+
+// threshold the value computed by the verifier of an achievement must reach, the value being
+// read from source_field of the json at source_url for verifiers querying an external api.
+// The verifier defaults to the verify_type, which batched and default achievements keep for
+// the frontend to pick the endpoint verifying them
+pub_struct!(Debug, Clone, Serialize, Deserialize; AchievementVerifyParams {
+    verifier: Option<String>,
+    threshold: f64,
+    source_url: Option<String>,
+    source_field: Option<String>,
 });
 
 pub_struct!(Debug, Serialize, Deserialize; AchievementCategoryDocument {
@@ -264,23 +376,158 @@ pub_struct!(Debug, Serialize, Deserialize; UserExperience {
     timestamp:i64,
 });
 
+// what granted the experience of a user_exp entry, legacy entries having none
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum XpSource {
+    Quest {
+        quest_id: u32,
+    },
+    Achievement {
+        achievement_id: u32,
+    },
+    Manual {
+        admin: String,
+        reason: String,
+    },
+    Reversal {
+        entry_id: ObjectId,
+        admin: String,
+        reason: String,
+    },
+}
+
 pub_struct!(Debug, Serialize, Deserialize; LeaderboardTable {
     experience:i64,
     timestamp:f64,
 });
 
-pub_struct!(Debug, Serialize, Deserialize; BoostTable {
-    amount: i32,
-    token: String,
-    expiry: i64,
-    quests: Vec<i32>,
-    winner: Option<Vec<String>>,
+pub_struct!(Debug, Clone, Serialize, Deserialize; StarknetIdProfile {
+    address: String,
+    domain: Option<String>,
+    pfp_contract: Option<String>,
+    pfp_id: Option<String>,
+});
+
+pub_struct!(Debug, Serialize, Deserialize; SeasonDocument {
     id: i32,
-    img_url: String,
     name: String,
-    hidden: bool,
-    num_of_winners: i64,
-    token_decimals: i64,
+    start: i64,
+    end: Option<i64>,
+    closed: bool,
+});
+
+pub_struct!(Debug, Serialize, Deserialize; SeasonStanding {
+    rank: u32,
+    address: String,
+    xp: i64,
+});
+
+pub_struct!(Debug, Serialize, Deserialize; SeasonSnapshotDocument {
+    season_id: i32,
+    closed_at: i64,
+    standings: Vec<SeasonStanding>,
+});
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BoostTable {
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub amount: String,
+    pub token: String,
+    pub expiry: i64,
+    pub quests: Vec<i32>,
+    pub winner: Option<Vec<String>>,
+    pub id: i32,
+    pub img_url: String,
+    pub name: String,
+    pub hidden: bool,
+    pub num_of_winners: i64,
+    pub token_decimals: i64,
+    pub rewards: Option<Vec<BoostReward>>,
+    pub weighting: Option<BoostWeighting>,
+    pub distribution: Option<BoostDistribution>,
+    pub tiers: Option<Vec<BoostTier>>,
+    pub raffle: Option<RaffleProof>,
+}
+
+// boost amounts used to be stored as integers before being stored as decimal strings
+pub fn deserialize_amount<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawAmount {
+        Integer(i64),
+        Decimal(String),
+    }
+    match RawAmount::deserialize(deserializer)? {
+        RawAmount::Integer(amount) => Ok(amount.to_string()),
+        RawAmount::Decimal(amount) => Ok(amount),
+    }
+}
+
+// an extra token paid by a boost on top of its main token
+pub_struct!(Clone, Debug, Serialize, Deserialize; BoostReward {
+    token: String,
+    amount: String,
+    decimals: i64,
+});
+
+// token is a symbol or an address of the token registry
+pub_struct!(Clone, Debug, Deserialize; BoostRewardQuery {
+    token: String,
+    amount: String,
+});
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BoostDistribution {
+    Raffle,
+    Fcfs,
+    ProRata,
+    Tiered,
+}
+
+// tier amounts are expressed in the boost main token, other tokens being paid in proportion
+pub_struct!(Clone, Debug, Serialize, Deserialize; BoostTier {
+    up_to_rank: u32,
+    amount: String,
+});
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BoostWeighting {
+    Uniform,
+    QuestCount,
+    Experience,
+    RootDomain,
+}
+
+pub_struct!(Debug, Serialize, Deserialize; BoostClaimDocument {
+    id: i32,
+    winner: String,
+    amount: String,
+    timestamp: i64,
+    transaction_hash: String,
+    // token paid, missing on claims of the main token emitted by single token contracts
+    token: Option<String>,
+    // hash of the block the claim was emitted in, to find claims of orphaned blocks
+    block_hash: Option<String>,
+    _cursor: BoostClaimCursor,
+});
+
+pub_struct!(Debug, Serialize, Deserialize; BoostClaimCursor {
+    from: u64,
+    to: Option<u64>,
+});
+
+pub_struct!(Clone, Debug, Serialize, Deserialize; RaffleProof {
+    algorithm_version: u32,
+    block_number: u64,
+    seed: String,
+    participants: Vec<String>,
+    weights: Option<Vec<u64>>,
 });
 
 pub_struct!(Debug, Serialize, Deserialize; NftBalance {
@@ -345,6 +592,7 @@ pub_struct!(Deserialize, Serialize, Debug; UserAchievementCategory {
     id: u32,
     completed: bool,
     verify_type: String,
+    verify_params: Option<AchievementVerifyParams>,
 });
 
 pub_struct!(Debug, Serialize, Deserialize; QuestCategoryDocument {
@@ -354,11 +602,69 @@ pub_struct!(Debug, Serialize, Deserialize; QuestCategoryDocument {
     img_url: String,
 });
 
+// engagement a twitter task checks on the linked X account of the address
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TwitterAction {
+    Follow { username: String },
+    Retweet { tweet_id: String },
+    Like { tweet_id: String },
+    Quote { tweet_id: String },
+}
+
+// social account linked to an address, tokens being kept to verify tasks without going through
+// OAuth again
+pub_struct!(Debug, Serialize, Deserialize; LinkedAccountDocument {
+    address: String,
+    provider: String,
+    account_id: String,
+    username: String,
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    expires_at: Option<i64>,
+    linked_at: i64,
+});
+
 pub_struct!(Debug, Serialize, Deserialize; JWTClaims {
     sub: String,
     exp: usize,
 });
 
+pub_struct!(Debug, Serialize, Deserialize; SessionClaims {
+    sub: String,
+    exp: usize,
+});
+
+// task an OAuth state was issued for, signed so that the callback can trust it
+pub_struct!(Debug, Serialize, Deserialize; OAuthStatePayload {
+    addr: String,
+    quest_id: i64,
+    task_id: u32,
+    exp: i64,
+    nonce: String,
+});
+
+// fields sent by the Telegram Login Widget, all of them being signed by the hash
+pub_struct!(Debug, Deserialize; TelegramAuthData {
+    id: i64,
+    first_name: Option<String>,
+    last_name: Option<String>,
+    username: Option<String>,
+    photo_url: Option<String>,
+    auth_date: i64,
+    hash: String,
+});
+
+pub_struct!(Clone, Debug; WalletSession {
+    addr: FieldElement,
+});
+
+pub_struct!(Debug, Serialize, Deserialize; SessionChallengeDocument {
+    addr: String,
+    nonce: String,
+    expiry: i64,
+});
+
 pub_struct!(Debug, Serialize, Deserialize; LoginDetails {
     user: String,
     code: String,
@@ -366,13 +672,17 @@ pub_struct!(Debug, Serialize, Deserialize; LoginDetails {
 
 pub_struct!(Deserialize; CreateBoostQuery {
     quest_id: i32,
-    amount: i32,
+    amount: String,
     token: String,
     num_of_winners: i64,
-    token_decimals: i64,
+    token_decimals: Option<i64>,
+    rewards: Option<Vec<BoostRewardQuery>>,
     name: String,
     img_url: String,
     expiry: i64,
+    weighting: Option<BoostWeighting>,
+    distribution: Option<BoostDistribution>,
+    tiers: Option<Vec<BoostTier>>,
 });
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1,6 +1,15 @@
-use crate::logger::Logger;
+use crate::common::boost_distribution::get_max_winners;
+use crate::common::leaderboard::{get_day_bucket, DAY_MS};
+use crate::common::quest_completion::record_completed_task;
+use crate::common::raffle::{
+    get_raffle_seed, get_raffle_weights, run_raffle, RAFFLE_ALGORITHM_VERSION,
+    WEIGHTED_RAFFLE_ALGORITHM_VERSION,
+};
+use crate::common::xp_ledger::credit_achievement;
 use crate::models::{
-    AchievementDocument, AppState, BoostTable, CompletedTasks, LeaderboardTable, QuestDocument, QuestTaskDocument, RewardSource, UserExperience
+    AchievementDocument, AppState, BlockSample, BoostDistribution, BoostTable, BoostWeighting,
+    CompletedTasks, LeaderboardTable, QuestDocument, QuestTaskDocument, RaffleProof, RewardSource,
+    UserExperience,
 };
 use async_trait::async_trait;
 use axum::{
@@ -9,14 +18,16 @@ use axum::{
     response::{IntoResponse, Response},
     Router,
 };
-use chrono::{Duration as dur, Utc};
+use chrono::Utc;
 use futures::TryStreamExt;
 use mongodb::options::FindOneOptions;
 use mongodb::{
-    bson::doc, options::UpdateOptions, results::UpdateResult, Collection, Cursor, Database,
-    IndexModel,
+    bson::{doc, to_bson, Document},
+    options::{IndexOptions, UpdateOptions},
+    results::UpdateResult,
+    Collection, Database, IndexModel,
 };
-use rand::distributions::{Distribution, Uniform};
+use num_bigint::BigUint;
 use serde_json::json;
 use starknet::signers::Signer;
 use starknet::{
@@ -57,6 +68,17 @@ pub async fn get_nft(
         true => nft_level as u64 + 100 * (rand::random::<u64>() % (2u64.pow(32))),
         false => (rand::random::<u64>() + nft_level as u64 * 0x2000000) * 100 + 99,
     };
+    let sig = sign_nft_mint(token_id, quest_id, task_id, addr, signer).await?;
+    Ok((token_id, sig))
+}
+
+async fn sign_nft_mint(
+    token_id: u64,
+    quest_id: u32,
+    task_id: u32,
+    addr: &FieldElement,
+    signer: &LocalWallet,
+) -> Result<Signature, Box<dyn std::error::Error + Send + Sync>> {
     let hashed = pedersen_hash(
         &pedersen_hash(
             &pedersen_hash(
@@ -67,7 +89,32 @@ pub async fn get_nft(
         ),
         addr,
     );
-    let sig = signer.sign_hash(&hashed).await?;
+    Ok(signer.sign_hash(&hashed).await?)
+}
+
+// achievements are minted like the tasks of a quest id reserved for them, the achievement id
+// standing for the task id so that the contract lets each of them be minted once per address.
+// Quest ids are i32, so this one is out of their range and no quest ever signs mints under it
+pub const ACHIEVEMENTS_QUEST_ID: u32 = u32::MAX;
+
+pub async fn get_achievement_nft(
+    achievement_id: u32,
+    addr: &FieldElement,
+    nft_level: u32,
+    signer: &LocalWallet,
+) -> Result<(u64, Signature), Box<dyn std::error::Error + Send + Sync>> {
+    // the level is kept in the last two digits and the achievement id in the next four
+    let token_id = (nft_level % 100) as u64
+        + 100 * (achievement_id % 10_000) as u64
+        + 1_000_000 * (rand::random::<u64>() % (2u64.pow(32)));
+    let sig = sign_nft_mint(
+        token_id,
+        ACHIEVEMENTS_QUEST_ID,
+        achievement_id,
+        addr,
+        signer,
+    )
+    .await?;
     Ok((token_id, sig))
 }
 
@@ -103,6 +150,29 @@ pub fn get_error_redirect(redirect_uri: String, error: String) -> Response {
     response.into_response()
 }
 
+// page of the quest users are sent back to once one of its tasks is verified through OAuth
+pub fn get_task_redirect_uri(
+    app_link: &str,
+    quest_id: i64,
+    return_url: Option<&str>,
+    task_id: u32,
+    res: bool,
+) -> String {
+    let base = match return_url {
+        Some(return_url) => return_url.to_string(),
+        None => format!("{}/quest/{}", app_link, quest_id),
+    };
+    let separator = if base.contains('?') { '&' } else { '?' };
+    format!("{}{}task_id={}&res={}", base, separator, task_id, res)
+}
+
+pub fn validate_return_url(return_url: &str) -> Result<(), String> {
+    match reqwest::Url::parse(return_url) {
+        Ok(url) if url.scheme() == "https" || url.scheme() == "http" => Ok(()),
+        _ => Err("return_url must be an http(s) url".to_string()),
+    }
+}
+
 pub fn success_redirect(redirect_uri: String) -> Response {
     let uri = match Uri::from_str(&redirect_uri) {
         Ok(uri) => uri,
@@ -128,6 +198,13 @@ pub trait CompletedTasksTrait {
         addr: FieldElement,
         task_id: u32,
     ) -> Result<UpdateResult, mongodb::error::Error>;
+
+    async fn upsert_sampled_completed_task(
+        &self,
+        addr: FieldElement,
+        task_id: u32,
+        block_samples: Vec<BlockSample>,
+    ) -> Result<UpdateResult, mongodb::error::Error>;
 }
 
 #[async_trait]
@@ -137,135 +214,17 @@ impl CompletedTasksTrait for AppState {
         addr: FieldElement,
         task_id: u32,
     ) -> Result<UpdateResult, mongodb::error::Error> {
-        let completed_tasks_collection: Collection<CompletedTasks> =
-            self.db.collection("completed_tasks");
-        let created_at = Utc::now().timestamp_millis();
-        let filter = doc! { "address": addr.to_string(), "task_id": task_id };
-        let update = doc! { "$setOnInsert": { "address": addr.to_string(), "task_id": task_id , "timestamp":created_at} };
-
-        let options = UpdateOptions::builder().upsert(true).build();
-
-        let result = completed_tasks_collection
-            .update_one(filter, update, options)
-            .await?;
-
-        match &result.upserted_id {
-            Some(_id) => {
-                let pipeline = vec![
-                    doc! {
-                        "$match": doc!{
-                        "address": addr.to_string(),
-                    },
-                    },
-                    doc! {
-                        "$lookup": doc! {
-                        "from": "tasks",
-                        "localField": "task_id",
-                        "foreignField": "id",
-                        "as": "associatedTask",
-                    },
-                    },
-                    doc! {
-                        "$unwind": "$associatedTask",
-                    },
-                    doc! {
-                       "$project": doc! {
-                        "address": "$address",
-                        "task_id": "$task_id",
-                       "quest_id": "$associatedTask.quest_id",
-                    },
-                    },
-                    doc! {
-                        "$group": doc! {
-                        "_id": "$quest_id",
-                        "done": doc! {
-                            "$sum": 1,
-                        },
-                    },
-                    },
-                    doc! {
-                        "$lookup": doc! {
-                        "from": "tasks",
-                        "localField": "_id",
-                        "foreignField": "quest_id",
-                        "as": "tasks",
-                    },
-                    },
-                    doc! {
-                        "$match": doc! {
-                        "$expr": {
-                            "$eq": [
-                            "$done",
-                            {
-                                "$size": "$tasks",
-                            },
-                            ],
-                        },
-                    },
-                    },
-                    doc! {
-                        "$match": doc! {
-                        "tasks": doc! {
-                            "$elemMatch": {
-                                "id": task_id,
-                            },
-                        },
-                    },
-                    },
-                    doc! {
-                        "$lookup": doc! {
-                        "from": "quests",
-                        "localField": "_id",
-                        "foreignField": "id",
-                        "as": "associatedQuests",
-                    }
-                    },
-                    doc! {
-                        "$unwind": "$associatedQuests",
-                    },
-                    doc! {
-                        "$project": doc! {
-                            "_id": 0,
-                            "experience": "$associatedQuests.experience",
-                        }
-                    },
-                ];
-                match completed_tasks_collection.aggregate(pipeline, None).await {
-                    Ok(mut cursor) => {
-                        let mut experience = 0;
-                        while let Some(response) = cursor.try_next().await.unwrap() {
-                            experience = response.get("experience").unwrap().as_i32().unwrap();
-                        }
-
-                        // return result if experience is 0 (quest is not completed)
-                        if experience == 0 {
-                            return Ok(result);
-                        }
+        self.upsert_sampled_completed_task(addr, task_id, vec![])
+            .await
+    }
 
-                        // save the user_exp document in the collection
-                        let user_exp_collection = self.db.collection("user_exp");
-                        // add doc with address ,experience and timestamp
-                        let timestamp: f64 = Utc::now().timestamp_millis() as f64;
-                        let document = doc! { "address": addr.to_string(), "experience":experience, "timestamp":timestamp};
-                        user_exp_collection.insert_one(document, None).await?;
-                        let view_collection: Collection<LeaderboardTable> =
-                            self.db.collection("leaderboard_table");
-                        update_leaderboard(
-                            view_collection,
-                            addr.to_string(),
-                            experience.into(),
-                            timestamp,
-                        )
-                        .await;
-                    }
-                    Err(_e) => {
-                        get_error("Error querying quests".to_string());
-                    }
-                }
-            }
-            None => {}
-        }
-        Ok(result)
+    async fn upsert_sampled_completed_task(
+        &self,
+        addr: FieldElement,
+        task_id: u32,
+        block_samples: Vec<BlockSample>,
+    ) -> Result<UpdateResult, mongodb::error::Error> {
+        record_completed_task(&self.db, addr, task_id, block_samples).await
     }
 }
 
@@ -330,37 +289,16 @@ impl AchievementsTrait for AppState {
             .update_one(filter, update, options)
             .await?;
 
-        match &result.upserted_id {
-            Some(_id) => {
-                // Check if the document was modified
-                let achievement_collection: Collection<AchievementDocument> =
-                    self.db.collection("achievements");
-                // Define a query using the `doc!` macro.
-                let query = doc! { "id": achievement_id };
-                let mut experience: i32 = 0;
-
-                let mut cursor = achievement_collection.find(query, None).await?;
-                // Iterate over the results.
-                while let Some(doc) = cursor.try_next().await? {
-                    experience = doc.experience as i32;
-                }
-
-                let user_exp_collection = self.db.collection("user_exp");
-                // add doc with address ,experience and timestamp
-                let timestamp: f64 = Utc::now().timestamp_millis() as f64;
-                let document = doc! { "address": addr.to_string(), "experience":experience, "timestamp":timestamp};
-                user_exp_collection.insert_one(document, None).await?;
-                let view_collection: Collection<LeaderboardTable> =
-                    self.db.collection("leaderboard_table");
-                update_leaderboard(
-                    view_collection,
+        if result.upserted_id.is_some() {
+            if let Some(achievement) = self.get_achievement(achievement_id).await? {
+                credit_achievement(
+                    &self.db,
                     addr.to_string(),
-                    experience.into(),
-                    timestamp,
+                    achievement_id,
+                    achievement.experience,
                 )
-                .await;
+                .await?;
             }
-            None => {}
         }
         Ok(result)
     }
@@ -405,16 +343,6 @@ pub trait DeployedTimesTrait {
     ) -> Result<UpdateResult, mongodb::error::Error>;
 }
 
-pub fn get_timestamp_from_days(days: i64) -> i64 {
-    // take input as week , month and all time and return the timestamp range
-    let time_gap = if days > 0 {
-        (Utc::now() - dur::days(days)).timestamp_millis()
-    } else {
-        0
-    };
-    time_gap
-}
-
 #[async_trait]
 impl DeployedTimesTrait for AppState {
     async fn upsert_deployed_timestamp(
@@ -446,29 +374,27 @@ pub async fn fetch_json_from_url(url: String) -> Result<serde_json::Value, Strin
     }
 }
 
-pub async fn update_leaderboard(
-    view_collection: Collection<LeaderboardTable>,
-    address: String,
-    experience: i64,
-    timestamp: f64,
-) {
-    // get current experience and new experience to it
-    let mut old_experience = 0;
-    let filter = doc! { "_id": &*address };
-    let mut cursor: Cursor<LeaderboardTable> = view_collection.find(filter, None).await.unwrap();
-    while let Some(doc) = cursor.try_next().await.unwrap() {
-        old_experience = doc.experience;
-    }
+pub async fn update_leaderboard(db: &Database, address: String, experience: i64, timestamp: f64) {
+    let view_collection: Collection<LeaderboardTable> = db.collection("leaderboard_table");
 
-    // update the view collection
+    // incrementing in place keeps concurrent credits of the same address from overwriting each other
     let filter = doc! { "_id": &*address };
-    let update =
-        doc! { "$set": { "experience": old_experience + experience, "timestamp": timestamp } };
+    let update = doc! { "$inc": { "experience": experience }, "$max": { "timestamp": timestamp } };
     let options = UpdateOptions::builder().upsert(true).build();
     view_collection
         .update_one(filter, update, options)
         .await
         .unwrap();
+
+    // windowed rankings sum the daily buckets they cover
+    let daily_collection = db.collection::<Document>("leaderboard_daily");
+    let filter = doc! { "address": &*address, "day": get_day_bucket(timestamp as i64) };
+    let update = doc! { "$inc": { "experience": experience }, "$max": { "timestamp": timestamp } };
+    let options = UpdateOptions::builder().upsert(true).build();
+    daily_collection
+        .update_one(filter, update, options)
+        .await
+        .unwrap();
 }
 
 pub async fn add_leaderboard_table(db: &Database) {
@@ -514,214 +440,336 @@ pub async fn add_leaderboard_table(db: &Database) {
         .create_index(compound_index, None)
         .await
         .unwrap();
-}
 
-pub async fn fetch_and_update_boosts_winner(
-    boost_collection: Collection<BoostTable>,
-    completed_tasks_collection: Collection<CompletedTasks>,
-    interval: u64,
-    logger: Logger,
-) {
-    loop {
-        let pipeline = vec![doc! {
-            "$match": {
-                "expiry":{
-                    "$lt": Utc::now().timestamp_millis()
+    // rebuild the daily buckets, merging on address and day requires a unique index on them
+    let daily_collection = db.collection::<Document>("leaderboard_daily");
+    let unique_options = IndexOptions::builder().unique(true).build();
+    let bucket_index = IndexModel::builder()
+        .keys(doc! { "address": 1, "day": 1 })
+        .options(unique_options)
+        .build();
+    daily_collection
+        .create_index(bucket_index, None)
+        .await
+        .unwrap();
+    let day_index = IndexModel::builder().keys(doc! { "day": 1 }).build();
+    daily_collection
+        .create_index(day_index, None)
+        .await
+        .unwrap();
+
+    let timestamp = doc! { "$toLong": "$timestamp" };
+    let daily_pipeline = vec![
+        doc! {
+            "$group": doc! {
+                "_id": doc! {
+                    "address": "$address",
+                    "day": doc! {
+                        "$subtract": [timestamp.clone(), doc! { "$mod": [timestamp.clone(), DAY_MS] }]
+                    }
                 },
-                "winner": {
-                    "$eq": null,
+                "experience": doc! {
+                    "$sum": "$experience"
                 },
+                "timestamp": doc! {
+                    "$max": "$timestamp"
+                }
             }
-        }];
-        match boost_collection.aggregate(pipeline, None).await {
-            Ok(mut cursor) => {
-                while let Some(doc) = cursor.try_next().await.unwrap() {
-                    let mut num_of_winners = doc.get("num_of_winners").unwrap().as_i32().unwrap();
-                    // use this variable to add some extra winners so that we have some extra winners incase anyone user repeats
-                    let extra_winners = 10;
-                    match doc.get("quests") {
-                        Some(quests_res) => {
-                            let quests = quests_res.as_array().unwrap();
-                            let mut address_list: Vec<FieldElement> = Vec::new();
-                            for quest in quests {
-                                let get_users_per_quest_pipeline = vec![
-                                    doc! {
-                                        "$lookup": doc! {
-                                            "from": "tasks",
-                                            "localField": "task_id",
-                                            "foreignField": "id",
-                                            "as": "associated_tasks"
-                                        }
-                                    },
-                                    doc! {
-                                        "$match": doc! {
-                                            "$expr": doc! {
-                                                "$eq": [
-                                                    doc! {
-                                                        "$first": "$associated_tasks.quest_id"
-                                                    },
-                                                    quest
-                                                ]
-                                            }
-                                        }
-                                    },
-                                    doc! {
-                                        "$group": doc! {
-                                            "_id": "$address",
-                                            "tasks_list": doc! {
-                                                "$push": doc! {
-                                                    "$arrayElemAt": [
-                                                        "$associated_tasks",
-                                                        0
-                                                    ]
-                                                }
-                                            }
-                                        }
-                                    },
-                                    doc! {
-                                        "$unwind": "$tasks_list"
-                                    },
-                                    doc! {
-                                        "$group": doc! {
-                                            "_id": doc! {
-                                                "address": "$_id",
-                                                "quest_id": "$tasks_list.quest_id"
-                                            },
-                                            "tasks_array": doc! {
-                                                "$push": "$tasks_list"
-                                            }
-                                        }
-                                    },
-                                    doc! {
-                                        "$project": doc! {
-                                            "_id": 0,
-                                            "address": "$_id.address",
-                                            "quest_id": "$_id.quest_id",
-                                            "tasks_array": 1
-                                        }
-                                    },
-                                    doc! {
-                                        "$lookup": doc! {
-                                            "from": "tasks",
-                                            "localField": "quest_id",
-                                            "foreignField": "quest_id",
-                                            "as": "associatedTasks"
-                                        }
-                                    },
-                                    doc! {
-                                        "$match": doc! {
-                                            "$expr": doc! {
-                                                "$eq": [
-                                                    doc! {
-                                                        "$size": "$tasks_array"
-                                                    },
-                                                    doc! {
-                                                        "$size": "$associatedTasks"
-                                                    }
-                                                ]
-                                            }
-                                        }
-                                    },
-                                    doc! {
-                                        "$project": doc! {
-                                            "address": "$address"
-                                        }
-                                    },
-                                    doc! {
-                                        "$sample":{
-                                            "size":num_of_winners+extra_winners
-                                        }
-                                    },
-                                ];
-                                match completed_tasks_collection
-                                    .aggregate(get_users_per_quest_pipeline, None)
-                                    .await
-                                {
-                                    Ok(mut cursor) => {
-                                        while let Some(doc) = cursor.try_next().await.unwrap() {
-                                            let address =
-                                                doc.get("address").unwrap().as_str().unwrap();
-                                            let formatted_address =
-                                                FieldElement::from_str(address).unwrap();
-                                            address_list.push(formatted_address);
-                                        }
-                                    }
-                                    Err(_err) => {}
-                                }
-                            }
+        },
+        doc! {
+            "$project": doc! {
+                "_id": 0,
+                "address": "$_id.address",
+                "day": "$_id.day",
+                "experience": 1,
+                "timestamp": 1
+            }
+        },
+        doc! { "$merge" : doc! { "into": "leaderboard_daily", "on": ["address", "day"], "whenMatched": "replace", "whenNotMatched": "insert" } },
+    ];
+    source_collection
+        .aggregate(daily_pipeline, None)
+        .await
+        .unwrap();
+}
 
-                            // skip if no user has completed quests
-                            if address_list.len() == 0 {
-                                continue;
-                            }
-                            let mut random_index;
-                            let mut winner_array: Vec<String> = Vec::new();
-
-                            // if length of address list is 1 then select the only user
-                            if address_list.len() == 1 {
-                                let winner = &address_list[0].to_string();
-                                let formatted_winner = FieldElement::from_str(winner).unwrap();
-                                winner_array.push(to_hex(formatted_winner));
-                            }
-                            // else select random users
-                            else {
-                                let mut current_winner_index = 0;
-                                // handle case when number of winners is greater than number of users then assign all users as winners
-                                if address_list.len() < num_of_winners as usize {
-                                    num_of_winners = address_list.len() as i32;
-                                }
-                                let mut iter_index = 0;
-                                loop {
-                                    let mut rng = rand::thread_rng();
-
-                                    let die = Uniform::new(0, address_list.len());
-                                    random_index = die.sample(&mut rng);
-
-                                    let winner = &address_list[random_index].to_string();
-                                    let formatted_winner = FieldElement::from_str(winner).unwrap();
-                                    if !winner_array.contains(&to_hex(formatted_winner)) {
-                                        winner_array.push(to_hex(formatted_winner));
-                                        current_winner_index += 1;
-                                    }
-                                    iter_index += 1;
-                                    if current_winner_index == (num_of_winners) as usize
-                                        || iter_index == address_list.len()
-                                    {
-                                        break;
-                                    }
-                                }
+// addresses having completed every task of one of the boost quests before its expiry,
+// along with the time their last task of that quest was completed
+async fn get_boost_participants(
+    completed_tasks_collection: &Collection<CompletedTasks>,
+    boost: &BoostTable,
+) -> Result<Vec<(FieldElement, i64)>, String> {
+    let mut participants = Vec::new();
+    for quest in &boost.quests {
+        let get_users_per_quest_pipeline = vec![
+            doc! {
+                "$match": doc! {
+                    "timestamp": doc! {
+                        "$lte": boost.expiry
+                    }
+                }
+            },
+            doc! {
+                "$lookup": doc! {
+                    "from": "tasks",
+                    "localField": "task_id",
+                    "foreignField": "id",
+                    "as": "associated_tasks"
+                }
+            },
+            doc! {
+                "$match": doc! {
+                    "$expr": doc! {
+                        "$eq": [
+                            doc! {
+                                "$first": "$associated_tasks.quest_id"
+                            },
+                            quest
+                        ]
+                    }
+                }
+            },
+            doc! {
+                "$group": doc! {
+                    "_id": "$address",
+                    "tasks_list": doc! {
+                        "$push": doc! {
+                            "$arrayElemAt": [
+                                "$associated_tasks",
+                                0
+                            ]
+                        }
+                    },
+                    "completed_at": doc! {
+                        "$max": "$timestamp"
+                    }
+                }
+            },
+            doc! {
+                "$unwind": "$tasks_list"
+            },
+            doc! {
+                "$group": doc! {
+                    "_id": doc! {
+                        "address": "$_id",
+                        "quest_id": "$tasks_list.quest_id"
+                    },
+                    "tasks_array": doc! {
+                        "$push": "$tasks_list"
+                    },
+                    "completed_at": doc! {
+                        "$first": "$completed_at"
+                    }
+                }
+            },
+            doc! {
+                "$project": doc! {
+                    "_id": 0,
+                    "address": "$_id.address",
+                    "quest_id": "$_id.quest_id",
+                    "tasks_array": 1,
+                    "completed_at": "$completed_at"
+                }
+            },
+            doc! {
+                "$lookup": doc! {
+                    "from": "tasks",
+                    "localField": "quest_id",
+                    "foreignField": "quest_id",
+                    "as": "associatedTasks"
+                }
+            },
+            doc! {
+                "$match": doc! {
+                    "$expr": doc! {
+                        "$eq": [
+                            doc! {
+                                "$size": "$tasks_array"
+                            },
+                            doc! {
+                                "$size": "$associatedTasks"
                             }
+                        ]
+                    }
+                }
+            },
+            doc! {
+                "$project": doc! {
+                    "address": "$address",
+                    "completed_at": "$completed_at"
+                }
+            },
+        ];
+        let mut cursor = completed_tasks_collection
+            .aggregate(get_users_per_quest_pipeline, None)
+            .await
+            .map_err(|e| format!("Error querying participants: {}", e))?;
+        while let Some(doc) = cursor
+            .try_next()
+            .await
+            .map_err(|e| format!("Error querying participants: {}", e))?
+        {
+            if let Some(address) = doc
+                .get_str("address")
+                .ok()
+                .and_then(|address| FieldElement::from_str(address).ok())
+            {
+                let completed_at = doc.get_i64("completed_at").unwrap_or(boost.expiry);
+                participants.push((address, completed_at));
+            }
+        }
+    }
+    Ok(participants)
+}
+
+async fn draw_boost_raffle(
+    state: &AppState,
+    boost_collection: &Collection<BoostTable>,
+    completed_tasks_collection: &Collection<CompletedTasks>,
+    boost: &BoostTable,
+) -> Result<(), String> {
+    // the draw waits for the seed block so that nobody can predict it before expiry
+    let Some((block_number, seed)) = get_raffle_seed(state, boost.expiry).await? else {
+        return Ok(());
+    };
+
+    // one entry per quest completed, used by the quest_count weighting
+    let entries: Vec<FieldElement> = get_boost_participants(completed_tasks_collection, boost)
+        .await?
+        .into_iter()
+        .map(|(addr, _)| addr)
+        .collect();
+    // skip if no user has completed quests
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut participants = entries.clone();
+    participants.sort_by_key(|felt| felt.to_bytes_be());
+    participants.dedup();
+
+    let (algorithm_version, weights) = match &boost.weighting {
+        None | Some(BoostWeighting::Uniform) => (RAFFLE_ALGORITHM_VERSION, None),
+        Some(weighting) => (
+            WEIGHTED_RAFFLE_ALGORITHM_VERSION,
+            Some(get_raffle_weights(state, weighting, &participants, &entries).await?),
+        ),
+    };
+    let winners: Vec<String> = run_raffle(
+        algorithm_version,
+        &participants,
+        weights.as_deref(),
+        seed,
+        boost.num_of_winners as usize,
+    )?
+    .into_iter()
+    .map(to_hex)
+    .collect();
+    let raffle = RaffleProof {
+        algorithm_version,
+        block_number,
+        seed: to_hex(seed),
+        participants: participants.into_iter().map(to_hex).collect(),
+        weights,
+    };
+    let raffle = to_bson(&raffle).map_err(|e| format!("Error serializing raffle: {}", e))?;
+
+    // the winner filter keeps a concurrent draw from overwriting a published result
+    boost_collection
+        .update_one(
+            doc! { "id": boost.id, "winner": null },
+            doc! { "$set": { "winner": winners, "raffle": raffle } },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error updating boost: {}", e))?;
+    Ok(())
+}
+
+// ranks completers by completion time, which is what the non raffle distributions pay out on
+async fn rank_boost_completers(
+    boost_collection: &Collection<BoostTable>,
+    completed_tasks_collection: &Collection<CompletedTasks>,
+    boost: &BoostTable,
+) -> Result<(), String> {
+    let mut completers = get_boost_participants(completed_tasks_collection, boost).await?;
+    // skip if no user has completed quests
+    if completers.is_empty() {
+        return Ok(());
+    }
+    completers.sort_by_key(|(addr, completed_at)| (*completed_at, addr.to_bytes_be()));
 
-                            let filter = doc! { "id": doc.get("id").unwrap().as_i32().unwrap() };
-                            let update = doc! { "$set": { "winner": winner_array  } };
-                            let options = UpdateOptions::builder().upsert(true).build();
-                            boost_collection
-                                .update_one(filter, update, options)
-                                .await
-                                .unwrap();
+    let mut winners: Vec<String> = Vec::new();
+    for (addr, _) in completers {
+        let addr = to_hex(addr);
+        if !winners.contains(&addr) {
+            winners.push(addr);
+        }
+    }
+    if let Some(max_winners) = get_max_winners(boost) {
+        winners.truncate(max_winners);
+    }
+
+    boost_collection
+        .update_one(
+            doc! { "id": boost.id, "winner": null },
+            doc! { "$set": { "winner": winners } },
+            None,
+        )
+        .await
+        .map_err(|e| format!("Error updating boost: {}", e))?;
+    Ok(())
+}
+
+pub async fn fetch_and_update_boosts_winner(state: Arc<AppState>, interval: u64) {
+    let boost_collection = state.db.collection::<BoostTable>("boosts");
+    let completed_tasks_collection = state.db.collection::<CompletedTasks>("completed_tasks");
+    loop {
+        let filter = doc! {
+            "expiry":{
+                "$lt": Utc::now().timestamp_millis()
+            },
+            "winner": {
+                "$eq": null,
+            },
+        };
+        match boost_collection.find(filter, None).await {
+            Ok(mut cursor) => {
+                while let Ok(Some(boost)) = cursor.try_next().await {
+                    let res = match boost.distribution {
+                        None | Some(BoostDistribution::Raffle) => {
+                            draw_boost_raffle(
+                                &state,
+                                &boost_collection,
+                                &completed_tasks_collection,
+                                &boost,
+                            )
+                            .await
                         }
-                        None => {
-                            logger.info("No winners found");
+                        Some(_) => {
+                            rank_boost_completers(
+                                &boost_collection,
+                                &completed_tasks_collection,
+                                &boost,
+                            )
+                            .await
                         }
+                    };
+                    if let Err(e) = res {
+                        state
+                            .logger
+                            .warning(format!("Error drawing boost {}: {}", boost.id, e));
                     }
                 }
             }
-            Err(_err) => logger.info(_err.to_string()),
+            Err(_err) => state.logger.info(_err.to_string()),
         };
 
         sleep(Duration::from_secs(interval)).await;
     }
 }
 
-pub fn run_boosts_raffle(db: &Database, interval: u64, logger: Logger) {
-    let boost_collection = db.collection::<BoostTable>("boosts");
-    let completed_tasks_collection = db.collection::<CompletedTasks>("completed_tasks");
-    tokio::spawn(fetch_and_update_boosts_winner(
-        boost_collection,
-        completed_tasks_collection,
-        interval,
-        logger,
-    ));
+pub fn run_boosts_raffle(state: Arc<AppState>, interval: u64) {
+    tokio::spawn(fetch_and_update_boosts_winner(state, interval));
 }
 
 pub async fn verify_task_auth(
@@ -863,6 +911,41 @@ pub fn parse_string(input: &str, address: FieldElement) -> String {
     result
 }
 
+// converts a human readable amount such as "1.5" into its integer value with the given decimals
+pub fn parse_decimal_amount(amount: &str, decimals: u32) -> Result<BigUint, String> {
+    let amount = amount.trim();
+    let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    if fraction.len() > decimals as usize {
+        return Err(format!(
+            "Amount {} has more than {} decimals",
+            amount, decimals
+        ));
+    }
+    let digits = format!(
+        "{}{}{}",
+        integer,
+        fraction,
+        "0".repeat(decimals as usize - fraction.len())
+    );
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid amount: {}", amount));
+    }
+    BigUint::parse_bytes(digits.as_bytes(), 10).ok_or(format!("Invalid amount: {}", amount))
+}
+
+// splits an integer into the (low, high) felts of a cairo u256
+pub fn to_u256(amount: &BigUint) -> Result<(FieldElement, FieldElement), String> {
+    if amount.bits() > 256 {
+        return Err(format!("Amount {} does not fit in a u256", amount));
+    }
+    let mask = (BigUint::from(1u32) << 128) - 1u32;
+    let to_felt = |value: BigUint| {
+        FieldElement::from_byte_slice_be(&value.to_bytes_be())
+            .map_err(|e| format!("Invalid amount {}: {}", amount, e))
+    };
+    Ok((to_felt(amount & &mask)?, to_felt(amount >> 128)?))
+}
+
 pub async fn get_next_task_id(
     task_collection: &Collection<QuestTaskDocument>,
     last_task_id: i64,
@@ -908,7 +991,7 @@ pub async fn check_if_claimed(
     contract: FieldElement,
     selector: FieldElement,
     calldata: Vec<FieldElement>,
-    source: RewardSource
+    source: RewardSource,
 ) -> bool {
     match read_contract(state, contract, selector, calldata).await {
         Ok(result) => result.get(0) == Some(&FieldElement::ZERO),